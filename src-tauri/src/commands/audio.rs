@@ -1,6 +1,8 @@
 use crate::audio_engine::engine::{AudioCommand, PlaybackState};
 use crate::audio_engine::AudioEngineState;
-use tauri::State;
+use crate::utils::audio::resolve_path;
+use crate::utils::waveform;
+use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
 pub fn audio_play(source: String, engine: State<'_, AudioEngineState>) {
@@ -67,3 +69,23 @@ pub fn audio_get_state(engine: State<'_, AudioEngineState>) -> PlaybackState {
     let state = engine.state.lock().unwrap().clone();
     state
 }
+
+/// 生成进度条波形概览（结果缓存在应用缓存目录，只需计算一次）
+/// 解码在阻塞线程池中进行，`buckets` 上限为 `waveform::MAX_BUCKETS`
+#[tauri::command]
+pub async fn generate_waveform(
+    app: AppHandle,
+    path: String,
+    buckets: usize,
+) -> Result<Vec<f32>, String> {
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("无法获取缓存目录: {}", e))?
+        .join("waveforms");
+    tauri::async_runtime::spawn_blocking(move || {
+        waveform::load_or_generate(&resolve_path(&path), buckets, &cache_dir)
+    })
+    .await
+    .map_err(|e| format!("波形生成任务异常退出: {}", e))?
+}
@@ -0,0 +1,11 @@
+//! 音频 CD 相关命令
+
+use crate::models::CdToc;
+use crate::utils::cdtoc;
+
+/// 读取光驱中音频 CD 的音轨列表和 MusicBrainz 光盘 ID
+/// `drive`：Windows 盘符（如 "D:"）或 Linux 光驱设备（如 "/dev/sr0"）
+#[tauri::command]
+pub fn read_cd_toc(drive: String) -> Result<CdToc, String> {
+    cdtoc::read_toc(&drive)
+}
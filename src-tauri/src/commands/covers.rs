@@ -0,0 +1,654 @@
+//! Cover cache Tauri commands
+
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::ipc::Channel;
+use tauri::{Manager, Runtime, State, UriSchemeContext};
+
+use crate::db::{self, CoverCacheConfig, DbState};
+use crate::models::{ScanError, StreamServerConfig};
+use crate::utils::audio::{canonical_path_key, resolve_path};
+use crate::utils::jellyfin;
+use crate::utils::cover::{
+    self, CoverCache, CoverFit, CoverFormat, CoverSize, CoverVariants, SpriteRect,
+};
+
+/// Parse a cover size name from the frontend ("small"/"mid"/"orig"), defaulting to mid
+pub fn parse_cover_size(size: Option<&str>) -> CoverSize {
+    match size {
+        Some("small") | Some("list") => CoverSize::Small,
+        Some("original") | Some("orig") => CoverSize::Original,
+        _ => CoverSize::Mid,
+    }
+}
+
+/// Default number of worker threads for extract_covers_batch
+const DEFAULT_EXTRACT_THREADS: usize = 4;
+
+/// Cover cache state wrapper
+pub struct CoverCacheState(pub Mutex<CoverCache>);
+
+/// Get cover URL by cover hash and size ("small"/"mid"/"orig")
+/// This is the primary method - frontend should use cover_hash from songs/albums.
+/// Pass `ext` when the file extension is known (e.g. "png" for an original)
+/// to skip probing. Returns None when the cover isn't cached so the UI can
+/// show a placeholder
+#[tauri::command]
+pub fn get_cover_url(
+    cover_cache: State<'_, CoverCacheState>,
+    hash: String,
+    size: Option<String>,
+    ext: Option<String>,
+) -> Result<Option<String>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+
+    let cover_size = parse_cover_size(size.as_deref());
+
+    Ok(cache.get_cover_url_with_ext(&hash, cover_size, ext.as_deref()))
+}
+
+/// Batch get cover URLs for multiple hashes
+/// More efficient than calling get_cover_url multiple times.
+/// `ext` is an extension hint tried first for every hash
+#[tauri::command]
+pub fn get_cover_urls_batch(
+    cover_cache: State<'_, CoverCacheState>,
+    hashes: Vec<String>,
+    size: Option<String>,
+    ext: Option<String>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+
+    let cover_size = parse_cover_size(size.as_deref());
+
+    let mut result = std::collections::HashMap::new();
+    for hash in hashes {
+        if let Some(url) = cache.get_cover_url_with_ext(&hash, cover_size, ext.as_deref()) {
+            result.insert(hash, url);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Get cover as a base64 data URL by cover hash and size
+/// Useful where the asset protocol URL doesn't resolve (e.g. mobile)
+#[tauri::command]
+pub fn get_cover_data_url(
+    cover_cache: State<'_, CoverCacheState>,
+    hash: String,
+    size: Option<String>,
+) -> Result<Option<String>, String> {
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+
+    let cover_size = parse_cover_size(size.as_deref());
+
+    Ok(cache
+        .get_cover_bytes(&hash, cover_size)
+        .map(|(data, mime)| format!("data:{};base64,{}", mime, BASE64.encode(data))))
+}
+
+/// Extract and cache the covers of many audio files in one call, on a
+/// dedicated pool of `threads` workers (default 4). Each file's
+/// `(path, hash)` is sent over `on_result` as soon as it is done, so the
+/// frontend can show progress; files without a cover (or that fail) report
+/// no hash. Repeated paths are processed once. Runs on the blocking pool so
+/// results stream in while the batch is still running.
+/// Returns the distinct hashes that were cached and the files that failed
+#[tauri::command]
+pub async fn extract_covers_batch(
+    cover_cache: State<'_, CoverCacheState>,
+    paths: Vec<String>,
+    threads: Option<usize>,
+    on_result: Channel<(String, Option<String>)>,
+) -> Result<CoverBatchResult, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        extract_covers(&cache, paths, threads, &on_result)
+    })
+    .await
+    .map_err(|e| format!("Cover extraction task failed: {}", e))?
+}
+
+/// Result of extract_covers_batch
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverBatchResult {
+    /// Distinct hashes that were cached, sorted
+    pub hashes: Vec<String>,
+    /// Files whose cover could not be read or cached
+    pub errors: Vec<ScanError>,
+}
+
+/// Body of extract_covers_batch
+fn extract_covers(
+    cache: &CoverCache,
+    paths: Vec<String>,
+    threads: Option<usize>,
+    on_result: &Channel<(String, Option<String>)>,
+) -> Result<CoverBatchResult, String> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or(DEFAULT_EXTRACT_THREADS).max(1))
+        .thread_name(|i| format!("cover-extract-{}", i))
+        .build()
+        .map_err(|e| format!("Failed to create cover thread pool: {}", e))?;
+
+    let mut seen_paths = HashSet::new();
+    let paths: Vec<String> = paths
+        .into_iter()
+        .filter(|path| seen_paths.insert(canonical_path_key(&resolve_path(path))))
+        .collect();
+
+    let results: Vec<Result<Option<String>, ScanError>> = pool.install(|| {
+        paths
+            .par_iter()
+            .map(|path| {
+                let result = cover::extract_and_cache_cover(&resolve_path(path), cache);
+                let hash = result.as_ref().ok().cloned().flatten();
+                let _ = on_result.send((path.clone(), hash));
+                result.map_err(|message| ScanError {
+                    path: path.clone(),
+                    message,
+                })
+            })
+            .collect()
+    });
+
+    let mut hashes = HashSet::new();
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(hash) => hashes.extend(hash),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    let mut hashes: Vec<String> = hashes.into_iter().collect();
+    hashes.sort();
+    Ok(CoverBatchResult { hashes, errors })
+}
+
+/// An embedded picture cached by extract_all_covers
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddedCover {
+    /// Picture type as named by lofty ("CoverFront", "CoverBack", "Artist", ...)
+    pub picture_type: String,
+    pub hash: String,
+}
+
+/// Cache every picture embedded in an audio file (front, back, artist, ...),
+/// e.g. for a booklet view
+#[tauri::command]
+pub async fn extract_all_covers(
+    cover_cache: State<'_, CoverCacheState>,
+    file_path: String,
+) -> Result<Vec<EmbeddedCover>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let covers = tauri::async_runtime::spawn_blocking(move || {
+        cover::extract_and_cache_all_covers(&resolve_path(&file_path), &cache)
+    })
+    .await
+    .map_err(|e| format!("Cover extraction task failed: {}", e))??;
+    Ok(covers
+        .into_iter()
+        .map(|(pic_type, hash)| EmbeddedCover {
+            picture_type: format!("{:?}", pic_type),
+            hash,
+        })
+        .collect())
+}
+
+/// Save a cached cover to a user-chosen path ("Save cover as...").
+/// The extension is adjusted to the cover's format; returns the path written
+#[tauri::command]
+pub fn export_cover(
+    cover_cache: State<'_, CoverCacheState>,
+    hash: String,
+    size: Option<String>,
+    out_path: String,
+) -> Result<String, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    let cover_size = parse_cover_size(size.as_deref());
+    cache
+        .export_cover(&hash, cover_size, &PathBuf::from(out_path))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+/// Get the BlurHash placeholder of a cached cover
+#[tauri::command]
+pub fn get_blurhash(
+    cover_cache: State<'_, CoverCacheState>,
+    hash: String,
+) -> Result<Option<String>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    Ok(cache.get_blurhash(&hash))
+}
+
+/// Get a cover's prominent colors as RGB triples, most prominent first
+#[tauri::command]
+pub fn get_cover_palette(
+    cover_cache: State<'_, CoverCacheState>,
+    hash: String,
+) -> Result<Option<Vec<[u8; 3]>>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    Ok(cache.palette(&hash))
+}
+
+/// Get a cover's dominant color as an RGB triple, e.g. for tinting the
+/// now-playing background
+#[tauri::command]
+pub fn get_cover_dominant_color(
+    cover_cache: State<'_, CoverCacheState>,
+    hash: String,
+) -> Result<Option<[u8; 3]>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    Ok(cache.dominant_color(&hash))
+}
+
+/// Get which variants (small/mid/orig) of a cover are cached
+#[tauri::command]
+pub fn get_cover_variants(
+    cover_cache: State<'_, CoverCacheState>,
+    hash: String,
+) -> Result<CoverVariants, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    Ok(cache.cover_variants(&hash))
+}
+
+/// Rebuild missing small/mid variants of a cover from its cached original
+#[tauri::command]
+pub fn regenerate_cover_variants(
+    cover_cache: State<'_, CoverCacheState>,
+    hash: String,
+) -> Result<(), String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    cache.regenerate_variants(&hash)
+}
+
+/// Cache a user-provided image and make it the preferred cover of an album
+/// (album IDs as returned by `db_get_all_albums` / `group_into_albums`,
+/// which share one ID scheme).
+/// Returns the cover hash
+#[tauri::command]
+pub fn set_album_cover(
+    cover_cache: State<'_, CoverCacheState>,
+    album_id: String,
+    cover_bytes: Vec<u8>,
+    mime: String,
+) -> Result<String, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let hash = cache.save_cover(&cover_bytes, Some(&mime))?;
+    cache.set_album_cover_override(&album_id, Some(&hash))?;
+    Ok(hash)
+}
+
+/// Go back to the album's embedded art
+#[tauri::command]
+pub fn clear_album_cover(
+    cover_cache: State<'_, CoverCacheState>,
+    album_id: String,
+) -> Result<(), String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    cache.set_album_cover_override(&album_id, None)
+}
+
+/// Get all album cover overrides (album ID -> cover hash)
+#[tauri::command]
+pub fn get_album_cover_overrides(
+    cover_cache: State<'_, CoverCacheState>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    Ok(cache.album_cover_overrides())
+}
+
+/// A sprite sheet of small cover variants
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverSpriteSheet {
+    /// Asset URL of the atlas image
+    pub url: String,
+    /// Cell of each packed cover, for CSS background positioning
+    pub rects: Vec<SpriteRect>,
+}
+
+/// Pack the small variants of many covers into sprite sheets for fast grid
+/// loads. Large sets are split over several sheets; each sheet is reused
+/// until its set of covers changes
+#[tauri::command]
+pub fn get_cover_sprite_sheet(
+    cover_cache: State<'_, CoverCacheState>,
+    hashes: Vec<String>,
+    columns: u32,
+) -> Result<Vec<CoverSpriteSheet>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let sheets = cache.build_sprite_sheets(&hashes, columns)?;
+    Ok(sheets
+        .into_iter()
+        .map(|(path, rects)| CoverSpriteSheet {
+            url: cover::asset_url(&path),
+            rects,
+        })
+        .collect())
+}
+
+/// Download a Jellyfin/Emby item's primary image into the cover cache, sending
+/// the server's auth header. `max_width` lets the server scale it down first.
+/// Returns the cover hash, or None when the item has no image
+#[tauri::command]
+pub async fn cache_stream_cover(
+    cover_cache: State<'_, CoverCacheState>,
+    config: StreamServerConfig,
+    item_id: String,
+    max_width: Option<u32>,
+) -> Result<Option<String>, String> {
+    if !config.is_jellyfin_like() {
+        return Err("Only Jellyfin/Emby servers are supported".to_string());
+    }
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let (url, headers) = jellyfin::cover_request(&config, &item_id, max_width);
+    cover::download_and_cache_cover_with_headers(&url, &headers, &cache).await
+}
+
+/// Parse a single `Range: bytes=...` header against a file length.
+/// Returns the inclusive byte range, or None when it can't be satisfied
+fn parse_range(range: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.trim().strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        // Suffix range: the last N bytes
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.checked_sub(suffix.min(len))?, len.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)?),
+        (start, end) => {
+            let end: u64 = end.parse().ok()?;
+            (start.parse().ok()?, end.min(len.checked_sub(1)?))
+        }
+    };
+    (start <= end).then_some((start, end))
+}
+
+fn empty_response(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+/// Handler of the `cover://` URI scheme, serving cached covers with HTTP range
+/// support so large originals (booklet scans) can stream in the viewer.
+/// URL: `cover://localhost/<hash>?size=orig` (`http://cover.localhost/...` on
+/// Windows and Android); size is "small", "mid" (default) or "orig"
+pub fn cover_protocol<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    let hash = request.uri().path().trim_start_matches('/');
+    // Nothing but a cover hash in the URL path reaches the cache's file lookup
+    if !cover::is_cover_hash(hash) {
+        return empty_response(StatusCode::NOT_FOUND);
+    }
+    let size = request
+        .uri()
+        .query()
+        .and_then(|query| query.split('&').find_map(|pair| pair.strip_prefix("size=")));
+    let cover_size = parse_cover_size(size);
+
+    let path = {
+        let state = ctx.app_handle().state::<CoverCacheState>();
+        let Ok(cache) = state.0.lock() else {
+            return empty_response(StatusCode::INTERNAL_SERVER_ERROR);
+        };
+        cache.get_cover_path(hash, cover_size)
+    };
+    let Some(path) = path else {
+        return empty_response(StatusCode::NOT_FOUND);
+    };
+    let Ok(mut file) = File::open(&path) else {
+        return empty_response(StatusCode::NOT_FOUND);
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let mime = cover::mime_from_extension(ext);
+
+    let response = Response::builder()
+        .header(header::CONTENT_TYPE, mime)
+        .header(header::ACCEPT_RANGES, "bytes")
+        // Content-addressed: a hash always maps to the same image
+        .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable");
+
+    // Multi-range requests get the full body, which RFC 9110 allows
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|range| !range.contains(','));
+    let Some(range) = range else {
+        let mut data = Vec::with_capacity(len as usize);
+        if file.read_to_end(&mut data).is_err() {
+            return empty_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        return response.status(StatusCode::OK).body(data).unwrap_or_default();
+    };
+
+    let Some((start, end)) = parse_range(range, len) else {
+        return Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new())
+            .unwrap_or_default();
+    };
+    let mut data = vec![0; (end - start + 1) as usize];
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_exact(&mut data).is_err() {
+        return empty_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    response
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+        .body(data)
+        .unwrap_or_default()
+}
+
+/// Get cover cache statistics
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverCacheStats {
+    pub file_count: usize,
+    pub total_size_bytes: u64,
+    pub total_size_mb: f64,
+    /// Cache root directory, e.g. for an "open in file manager" button
+    pub cache_dir: String,
+}
+
+#[tauri::command]
+pub fn get_cover_cache_stats(
+    cover_cache: State<'_, CoverCacheState>,
+) -> Result<CoverCacheStats, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    let stats = cache.get_stats();
+
+    Ok(CoverCacheStats {
+        file_count: stats.file_count,
+        total_size_bytes: stats.total_size,
+        total_size_mb: stats.total_size as f64 / 1024.0 / 1024.0,
+        cache_dir: cache.cache_dir().to_string_lossy().to_string(),
+    })
+}
+
+/// Apply a cover cache configuration on top of `cache`'s current settings
+pub fn apply_cover_cache_config(
+    cache: CoverCache,
+    config: &CoverCacheConfig,
+) -> Result<CoverCache, String> {
+    let mid_quality = config.mid_quality.unwrap_or(cover::DEFAULT_MID_QUALITY);
+    let small_quality = config.small_quality.unwrap_or(cover::DEFAULT_SMALL_QUALITY);
+    let format = match config.format.as_deref() {
+        None | Some("jpeg") | Some("jpg") => CoverFormat::Jpeg { quality: mid_quality },
+        Some("webp") => CoverFormat::WebP { quality: mid_quality },
+        Some(other) => return Err(format!("Unknown cover format: {}", other)),
+    };
+    let fit = match config.fit.as_deref() {
+        None | Some("fill") => CoverFit::Fill,
+        Some("contain") => CoverFit::Contain { pad: config.pad_color },
+        Some(other) => return Err(format!("Unknown cover fit: {}", other)),
+    };
+
+    let mut cache = cache
+        .with_format(format)
+        .with_quality(mid_quality, small_quality)
+        .with_sizes(
+            config.small_size.unwrap_or(cover::DEFAULT_SMALL_SIZE),
+            config.mid_size.unwrap_or(cover::DEFAULT_MID_SIZE),
+        )
+        .with_fit(fit)
+        .with_progressive(config.progressive)
+        .with_strip_metadata(config.strip_metadata)
+        .with_perceptual_dedupe(config.perceptual_dedupe);
+    if let Some(max_bytes) = config.max_bytes {
+        cache = cache.with_max_bytes(max_bytes);
+    }
+    if let Some(names) = &config.folder_art_names {
+        cache = cache.with_folder_art_names(names.clone());
+    }
+    if let Some(threads) = config.threads {
+        cache = cache.with_pool(threads)?;
+    }
+    if let Some(min) = config.min_original_dimension {
+        cache = cache.with_min_original_dimension(min);
+    }
+    if let Some(names) = &config.generated_sizes {
+        let sizes: Vec<_> = names.iter().map(|name| parse_cover_size(Some(name))).collect();
+        cache = cache.with_generated_sizes(&sizes);
+    }
+    if let Some(roots) = &config.fallback_roots {
+        cache = cache.with_fallback_roots(roots.iter().map(PathBuf::from).collect());
+    }
+    Ok(cache)
+}
+
+/// Get the persisted cover cache configuration
+#[tauri::command]
+pub fn get_cover_cache_config(db: State<'_, DbState>) -> Result<CoverCacheConfig, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    db::servers::get_cover_cache_config(&conn).map_err(|e| e.to_string())
+}
+
+/// Save the cover cache configuration and apply it to the app-wide cache.
+/// Covers cached with other sizes or qualities are still served until
+/// `regenerate_cover_variants` rebuilds them. Without `fallbackRoots` the
+/// saved fallback roots are kept
+#[tauri::command]
+pub fn set_cover_cache_config(
+    db: State<'_, DbState>,
+    cover_cache: State<'_, CoverCacheState>,
+    mut config: CoverCacheConfig,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    if config.fallback_roots.is_none() {
+        config.fallback_roots = db::servers::get_cover_cache_config(&conn)
+            .map_err(|e| e.to_string())?
+            .fallback_roots;
+    }
+
+    let updated = apply_cover_cache_config(cache.with_default_settings(), &config)?;
+    updated
+        .ensure_dirs()
+        .map_err(|e| format!("Failed to create cover cache directories: {}", e))?;
+
+    db::servers::save_cover_cache_config(&conn, &config).map_err(|e| e.to_string())?;
+    *cache = updated;
+    Ok(())
+}
+
+/// Set the extra cache roots searched after the primary cache directory,
+/// in order (e.g. an archive disk holding older covers), and persist them
+/// with the cover cache configuration. New covers are still written to the
+/// primary directory
+#[tauri::command]
+pub fn set_cover_cache_fallback_roots(
+    db: State<'_, DbState>,
+    cover_cache: State<'_, CoverCacheState>,
+    roots: Vec<String>,
+) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    let mut config = db::servers::get_cover_cache_config(&conn).map_err(|e| e.to_string())?;
+    let updated = cache
+        .clone()
+        .with_fallback_roots(roots.iter().map(PathBuf::from).collect());
+    config.fallback_roots = Some(roots);
+
+    db::servers::save_cover_cache_config(&conn, &config).map_err(|e| e.to_string())?;
+    *cache = updated;
+    Ok(())
+}
+
+/// Clean up orphaned covers (not referenced by any song)
+/// If the library has no cover hashes at all, nothing is removed unless
+/// `confirm_empty` is set. With `dry_run` only the paths that would be
+/// removed are returned
+#[tauri::command]
+pub fn cleanup_orphaned_covers(
+    db: State<'_, DbState>,
+    cover_cache: State<'_, CoverCacheState>,
+    confirm_empty: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+
+    let valid_hashes = referenced_cover_hashes(&conn)?;
+    cache.cleanup_orphaned(
+        &valid_hashes,
+        confirm_empty.unwrap_or(false),
+        dry_run.unwrap_or(false),
+    )
+}
+
+/// Get all cover hashes referenced by songs in the DB
+pub fn referenced_cover_hashes(conn: &rusqlite::Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT cover_hash FROM songs WHERE cover_hash IS NOT NULL")
+        .map_err(|e| e.to_string())?;
+
+    let hashes = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(hashes)
+}
+
+/// Remove corrupt cover cache entries (e.g. zero-length files after an
+/// unclean shutdown). Returns the affected hashes so they can be re-extracted
+#[tauri::command]
+pub async fn verify_cover_cache(
+    cover_cache: State<'_, CoverCacheState>,
+) -> Result<Vec<String>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    tauri::async_runtime::spawn_blocking(move || cache.verify())
+        .await
+        .map_err(|e| format!("Cover verification task failed: {}", e))
+}
+
+/// Clear all cover cache
+/// With `dry_run` only the paths that would be removed are returned
+#[tauri::command]
+pub fn clear_cover_cache(
+    cover_cache: State<'_, CoverCacheState>,
+    dry_run: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+    cache.clear_all(dry_run.unwrap_or(false))
+}
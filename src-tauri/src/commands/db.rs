@@ -1,5 +1,6 @@
 //! Database Tauri commands
 
+use crate::commands::CoverCacheState;
 use crate::db::{
     self, DbAlbum, DbArtist, DbSong, DbState, DbStreamServer, ScanConfig, SongInput,
     StreamServerInput,
@@ -42,6 +43,7 @@ pub struct MigrationStreamConfig {
     pub server_name: String,
     pub server_url: String,
     pub username: String,
+    #[serde(default, deserialize_with = "crate::models::null_as_empty")]
     pub password: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub access_token: Option<String>,
@@ -57,10 +59,25 @@ pub fn db_get_all_songs(db: State<'_, DbState>) -> Result<Vec<DbSong>, String> {
 }
 
 /// Get all albums (aggregated from songs)
+/// User-chosen album covers (set_album_cover) replace the embedded art
 #[tauri::command]
-pub fn db_get_all_albums(db: State<'_, DbState>) -> Result<Vec<DbAlbum>, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-    db::albums::get_all_albums(&conn).map_err(|e| e.to_string())
+pub fn db_get_all_albums(
+    db: State<'_, DbState>,
+    cover_cache: State<'_, CoverCacheState>,
+) -> Result<Vec<DbAlbum>, String> {
+    let mut albums = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        db::albums::get_all_albums(&conn).map_err(|e| e.to_string())?
+    };
+
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let overrides = cache.album_cover_overrides();
+    for album in &mut albums {
+        if let Some(hash) = overrides.get(&album.id) {
+            album.cover_hash = Some(hash.clone());
+        }
+    }
+    Ok(albums)
 }
 
 /// Get all artists (aggregated from songs)
@@ -192,6 +209,9 @@ pub fn db_migrate_from_localstorage(
             server_song_id: None,
             stream_info: if is_stream { Some(file_path) } else { None },
             file_modified: None,
+            album_artist: None,
+            musicbrainz_album_id: None,
+            compilation: false,
         };
 
         if is_stream {
@@ -267,114 +287,6 @@ pub fn db_get_library_stats(db: State<'_, DbState>) -> Result<LibraryStats, Stri
     })
 }
 
-// ============ Cover Cache Commands ============
-
-use crate::utils::cover::{CoverCache, CoverSize};
-use std::sync::Mutex;
-
-/// Cover cache state wrapper
-pub struct CoverCacheState(pub Mutex<CoverCache>);
-
-/// Get cover URL by cover hash and size
-/// This is the primary method - frontend should use cover_hash from songs/albums
-#[tauri::command]
-pub fn get_cover_url(
-    cover_cache: State<'_, CoverCacheState>,
-    hash: String,
-    size: Option<String>,
-) -> Result<Option<String>, String> {
-    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
-
-    let cover_size = match size.as_deref() {
-        Some("small") | Some("list") => CoverSize::Small,
-        Some("original") | Some("orig") => CoverSize::Original,
-        _ => CoverSize::Mid,
-    };
-
-    Ok(cache.get_cover_url(&hash, cover_size))
-}
-
-/// Batch get cover URLs for multiple hashes
-/// More efficient than calling get_cover_url multiple times
-#[tauri::command]
-pub fn get_cover_urls_batch(
-    cover_cache: State<'_, CoverCacheState>,
-    hashes: Vec<String>,
-    size: Option<String>,
-) -> Result<std::collections::HashMap<String, String>, String> {
-    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
-
-    let cover_size = match size.as_deref() {
-        Some("small") | Some("list") => CoverSize::Small,
-        Some("original") | Some("orig") => CoverSize::Original,
-        _ => CoverSize::Mid,
-    };
-
-    let mut result = std::collections::HashMap::new();
-    for hash in hashes {
-        if let Some(url) = cache.get_cover_url(&hash, cover_size) {
-            result.insert(hash, url);
-        }
-    }
-
-    Ok(result)
-}
-
-/// Get cover cache statistics
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CoverCacheStats {
-    pub file_count: usize,
-    pub total_size_bytes: u64,
-    pub total_size_mb: f64,
-}
-
-#[tauri::command]
-pub fn get_cover_cache_stats(
-    cover_cache: State<'_, CoverCacheState>,
-) -> Result<CoverCacheStats, String> {
-    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
-    let stats = cache.get_stats();
-
-    Ok(CoverCacheStats {
-        file_count: stats.file_count,
-        total_size_bytes: stats.total_size,
-        total_size_mb: stats.total_size as f64 / 1024.0 / 1024.0,
-    })
-}
-
-/// Clean up orphaned covers (not referenced by any song)
-#[tauri::command]
-pub fn cleanup_orphaned_covers(
-    db: State<'_, DbState>,
-    cover_cache: State<'_, CoverCacheState>,
-) -> Result<usize, String> {
-    let conn = db.0.lock().map_err(|e| e.to_string())?;
-    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
-
-    // Get all cover hashes from DB
-    let mut stmt = conn
-        .prepare("SELECT DISTINCT cover_hash FROM songs WHERE cover_hash IS NOT NULL")
-        .map_err(|e| e.to_string())?;
-
-    let valid_hashes: Vec<String> = stmt
-        .query_map([], |row| row.get(0))
-        .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
-        .collect();
-
-    cache.cleanup_orphaned(&valid_hashes)
-}
-
-/// Clear all cover cache
-#[tauri::command]
-pub fn clear_cover_cache(
-    cover_cache: State<'_, CoverCacheState>,
-) -> Result<usize, String> {
-    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
-    cache.clear_all()
-}
-
 /// Clean up songs whose files no longer exist
 #[tauri::command]
 pub fn cleanup_missing_songs(db: State<'_, DbState>) -> Result<usize, String> {
@@ -399,6 +311,22 @@ pub fn cleanup_missing_songs(db: State<'_, DbState>) -> Result<usize, String> {
     Ok(count)
 }
 
+/// Switch to the library database at `path` (created and migrated as needed),
+/// e.g. to keep the library and its metadata cache on another drive
+#[tauri::command]
+pub fn open_library_db(db: State<'_, DbState>, path: String) -> Result<(), String> {
+    let conn = db::open_db(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+    *db.0.lock().map_err(|e| e.to_string())? = conn;
+    Ok(())
+}
+
+/// Clear the scan metadata cache so every file is parsed again
+#[tauri::command]
+pub fn db_clear_metadata_cache(db: State<'_, DbState>) -> Result<usize, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    db::metadata::clear_metadata_cache(&conn).map_err(|e| e.to_string())
+}
+
 // ============ File Watcher Commands ============
 
 #[tauri::command]
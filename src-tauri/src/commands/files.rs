@@ -0,0 +1,168 @@
+//! 文件整理相关命令（移动 / 重命名 / 删除）
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::State;
+
+use crate::commands::{referenced_cover_hashes, CoverCacheState};
+use crate::db::{self, DbState};
+use crate::models::{DeletedSong, MovedSong};
+use crate::utils::audio::{resolve_path, song_id};
+use crate::utils::{cover, lyrics};
+
+/// 移动（重命名）文件；跨磁盘 / 分区时 rename 会失败，改为复制后删除源文件
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to).map_err(|e| format!("无法移动文件: {}", e))?;
+    fs::remove_file(from).map_err(|e| format!("已复制但无法删除源文件: {}", e))
+}
+
+/// 移动或重命名歌曲文件，目标目录不存在时自动创建
+/// 同名的 .lrc 歌词文件一并移动（失败时原因见 `lyrics_error`）；
+/// 目标已存在时报错，除非 `overwrite` 为 true
+/// 歌曲 ID 由路径生成，返回新的 ID 和封面哈希，便于前端重新关联
+#[tauri::command]
+pub fn move_song(
+    cover_cache: State<'_, CoverCacheState>,
+    from: String,
+    to: String,
+    overwrite: Option<bool>,
+) -> Result<MovedSong, String> {
+    let from_path = resolve_path(&from);
+    let to_path = resolve_path(&to);
+
+    if !from_path.is_file() {
+        return Err(format!("文件不存在: {}", from));
+    }
+    if to_path.exists() && !overwrite.unwrap_or(false) {
+        return Err(format!("目标文件已存在: {}", to));
+    }
+    if let Some(parent) = to_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("无法创建目标目录: {}", e))?;
+    }
+
+    let lrc_path = lyrics::find_sidecar(&from_path);
+    move_file(&from_path, &to_path)?;
+
+    // 歌词文件移动失败不影响歌曲本身，原因随结果返回
+    let lyrics_error = lrc_path.and_then(|lrc_path| {
+        let lrc_ext = lrc_path.extension().unwrap_or_default();
+        move_file(&lrc_path, &to_path.with_extension(lrc_ext))
+            .err()
+            .map(|e| format!("移动歌词文件失败 {}: {}", lrc_path.display(), e))
+    });
+
+    // 封面按内容寻址，移动后哈希不变；顺便确保已缓存（新目录可能有不同的 folder.jpg）
+    let cover_hash = {
+        let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+        cover::extract_and_cache_cover(&to_path, &cache).ok().flatten()
+    };
+
+    Ok(MovedSong {
+        old_id: song_id(&from_path),
+        id: song_id(&to_path),
+        file_path: to_path.to_string_lossy().to_string(),
+        cover_hash,
+        lyrics_error,
+    })
+}
+
+/// 删除单个文件：移入回收站，或直接删除
+fn remove_file(path: &Path, to_trash: bool) -> Result<(), String> {
+    if !to_trash {
+        return fs::remove_file(path).map_err(|e| format!("无法删除文件: {}", e));
+    }
+
+    #[cfg(desktop)]
+    {
+        trash::delete(path).map_err(|e| format!("无法移入回收站: {}", e))
+    }
+    #[cfg(not(desktop))]
+    {
+        Err("当前平台不支持回收站".to_string())
+    }
+}
+
+/// 判断文件是否位于某个音乐库目录内（比较规范化路径，避免 ".." 或符号链接绕过）
+fn is_in_library(path: &Path, roots: &[String]) -> bool {
+    let Ok(path) = fs::canonicalize(path) else {
+        return false;
+    };
+    roots
+        .iter()
+        .filter_map(|root| fs::canonicalize(root).ok())
+        .any(|root: PathBuf| path.starts_with(root))
+}
+
+/// 删除歌曲文件（`to_trash` 为 true 时移入系统回收站），同名 .lrc 歌词一并删除，
+/// 并从数据库中移除对应歌曲（按传入的原始路径、解析后的路径或歌曲 ID 匹配，
+/// 以 file:// URL 保存的歌曲也能移除）。只允许删除已配置的音乐库目录中的文件；
+/// `cleanup_covers` 为 true 时顺便清理不再被引用的封面
+#[tauri::command]
+pub fn delete_song(
+    db: State<'_, DbState>,
+    cover_cache: State<'_, CoverCacheState>,
+    path: String,
+    to_trash: bool,
+    cleanup_covers: Option<bool>,
+) -> Result<DeletedSong, String> {
+    let file_path = resolve_path(&path);
+
+    if file_path.is_dir() {
+        return Err(format!("不能删除目录: {}", path));
+    }
+    if !file_path.is_file() {
+        return Err(format!("文件不存在: {}", path));
+    }
+
+    let roots = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        db::get_scan_config(&conn)
+            .map_err(|e| e.to_string())?
+            .map(|config| config.directories)
+            .unwrap_or_default()
+    };
+    if !is_in_library(&file_path, &roots) {
+        return Err(format!("文件不在音乐库目录中: {}", path));
+    }
+
+    // 移入回收站可能较慢，期间不持有数据库锁
+    let lrc_path = lyrics::find_sidecar(&file_path);
+    remove_file(&file_path, to_trash)?;
+    let lyrics_error = lrc_path.and_then(|lrc_path| {
+        remove_file(&lrc_path, to_trash)
+            .err()
+            .map(|e| format!("删除歌词文件失败 {}: {}", lrc_path.display(), e))
+    });
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let removed_rows = conn
+        .execute(
+            "DELETE FROM songs WHERE file_path = ?1 OR file_path = ?2 OR id = ?3",
+            [
+                path.as_str(),
+                file_path.to_string_lossy().as_ref(),
+                song_id(&file_path).as_str(),
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut cover_error = None;
+    if cleanup_covers.unwrap_or(false) {
+        let valid_hashes = referenced_cover_hashes(&conn)?;
+        drop(conn);
+        let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+        cover_error = cache
+            .cleanup_orphaned(&valid_hashes, false, false)
+            .err()
+            .map(|e| format!("清理封面失败: {}", e));
+    }
+    Ok(DeletedSong {
+        removed_rows,
+        lyrics_error,
+        cover_error,
+    })
+}
@@ -0,0 +1,122 @@
+//! 资料库相关命令：在前端传入的歌曲列表上执行搜索等计算
+
+use std::path::Path;
+
+use tauri::State;
+
+use crate::commands::CoverCacheState;
+use crate::models::{Album, LibraryStats, ScannedSong};
+use crate::utils::{albums, search};
+use crate::utils::sort::{self, natural_cmp};
+
+/// 搜索歌曲（忽略大小写和变音符号），返回匹配歌曲在列表中的下标，
+/// 按字段优先级（标题 > 艺术家 > 专辑）排序
+#[tauri::command]
+pub fn search_songs(songs: Vec<ScannedSong>, query: String) -> Vec<usize> {
+    search::search(&songs, &query)
+}
+
+/// 排序歌曲，返回排序后的下标
+/// `field` 为 "title" / "artist" / "album"（默认 title）；`pinyin` 默认为 true，
+/// 中文按拼音排序，否则按字符自然排序
+#[tauri::command]
+pub fn sort_songs(
+    songs: Vec<ScannedSong>,
+    field: Option<String>,
+    pinyin: Option<bool>,
+) -> Vec<usize> {
+    let value = |song: &ScannedSong| -> String {
+        match field.as_deref() {
+            Some("artist") => song.artist.clone(),
+            Some("album") => song.album.clone(),
+            _ => song.title.clone(),
+        }
+    };
+
+    let mut keyed: Vec<(String, usize)> = if pinyin.unwrap_or(true) {
+        songs
+            .iter()
+            .enumerate()
+            .map(|(i, song)| (sort::pinyin_sort_key(&value(song)), i))
+            .collect()
+    } else {
+        songs.iter().enumerate().map(|(i, song)| (value(song), i)).collect()
+    };
+    keyed.sort_by(|a, b| natural_cmp(&a.0, &b.0));
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
+
+/// 拼音首字母（"周杰伦" -> "zjl"）
+#[tauri::command]
+pub fn pinyin_initials(text: String) -> String {
+    sort::pinyin_initials(&text)
+}
+
+/// 索引栏字母（A-Z，其余为 '#'），供列表按首字母跳转
+#[tauri::command]
+pub fn index_letters(texts: Vec<String>) -> Vec<char> {
+    texts.iter().map(|text| sort::index_letter(text)).collect()
+}
+
+/// 将歌曲按专辑分组（专辑艺术家 + 专辑名，有 MusicBrainz 专辑 ID 时以其为准）
+/// 用户设置的专辑封面（set_album_cover）优先于内嵌封面
+#[tauri::command]
+pub fn group_into_albums(
+    cover_cache: State<'_, CoverCacheState>,
+    songs: Vec<ScannedSong>,
+) -> Result<Vec<Album>, String> {
+    let mut albums = albums::group_into_albums(&songs);
+
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let overrides = cache.album_cover_overrides();
+    for album in &mut albums {
+        if let Some(hash) = overrides.get(&album.id) {
+            album.cover_hash = Some(hash.clone());
+        }
+    }
+    Ok(albums)
+}
+
+/// 码率区间（kbps）
+fn bitrate_bucket(bitrate: Option<u32>) -> &'static str {
+    match bitrate {
+        None | Some(0) => "unknown",
+        Some(1..=127) => "0-127",
+        Some(128..=191) => "128-191",
+        Some(192..=255) => "192-255",
+        Some(256..=319) => "256-319",
+        Some(320..=999) => "320-999",
+        Some(_) => "1000+",
+    }
+}
+
+/// 一次遍历统计资料库：歌曲数、总时长、总大小、格式与码率分布、高解析度歌曲数
+#[tauri::command]
+pub fn library_stats(songs: Vec<ScannedSong>) -> LibraryStats {
+    let mut stats = LibraryStats::default();
+
+    for song in &songs {
+        stats.total_tracks += 1;
+        stats.total_duration += song.duration;
+        stats.total_size += song.file_size;
+
+        let format = Path::new(&song.file_path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| "unknown".to_string());
+        *stats.by_format.entry(format).or_default() += 1;
+        *stats
+            .by_bitrate
+            .entry(bitrate_bucket(song.bitrate).to_string())
+            .or_default() += 1;
+
+        if song.is_hr.unwrap_or(false) {
+            stats.hi_res_tracks += 1;
+        }
+        if song.is_sq.unwrap_or(false) {
+            stats.lossless_tracks += 1;
+        }
+    }
+
+    stats
+}
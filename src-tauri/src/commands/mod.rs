@@ -3,9 +3,21 @@ pub mod scanner;
 pub mod db;
 pub mod scan;
 pub mod audio;
+pub mod covers;
+pub mod tags;
+pub mod playlist;
+pub mod library;
+pub mod files;
+pub mod cd;
 
 pub use streaming::*;
 pub use scanner::*;
 pub use db::*;
 pub use scan::*;
 pub use audio::*;
+pub use covers::*;
+pub use tags::*;
+pub use playlist::*;
+pub use library::*;
+pub use files::*;
+pub use cd::*;
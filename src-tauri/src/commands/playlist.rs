@@ -0,0 +1,70 @@
+//! 播放列表导入导出相关命令
+
+use std::path::Path;
+
+use rayon::prelude::*;
+
+use crate::models::{MissingPlaylistEntry, PlaylistFormat, PlaylistImport};
+use crate::utils::audio::{is_audio_file, read_metadata, resolve_path};
+use crate::utils::playlist::{self, PlaylistEntry};
+
+/// 将歌曲列表导出为播放列表文件，#EXTINF 使用歌曲时长和 "艺术家 - 标题"
+/// 读取失败的文件仍会写入，时长记为未知
+#[tauri::command]
+pub fn export_playlist(
+    paths: Vec<String>,
+    out_path: String,
+    format: PlaylistFormat,
+) -> Result<(), String> {
+    let entries: Vec<PlaylistEntry> = paths
+        .par_iter()
+        .map(|file_path| {
+            let path = resolve_path(file_path);
+            let song = read_metadata(&path).ok();
+            PlaylistEntry {
+                duration: song.as_ref().map(|s| s.duration),
+                title: song.map(|s| {
+                    if s.artist.is_empty() {
+                        s.title
+                    } else {
+                        format!("{} - {}", s.artist, s.title)
+                    }
+                }),
+                path,
+            }
+        })
+        .collect();
+
+    playlist::write_playlist(&entries, Path::new(&out_path), format)
+}
+
+/// 导入 M3U / M3U8 / PLS 播放列表：相对路径相对于播放列表所在目录解析，
+/// 读取仍存在的音轨的元数据；不存在或无法读取的条目单独返回
+#[tauri::command]
+pub fn import_playlist(path: String) -> Result<PlaylistImport, String> {
+    let entries = playlist::read_playlist(&resolve_path(&path))?;
+
+    let results: Vec<Result<_, MissingPlaylistEntry>> = entries
+        .into_par_iter()
+        .map(|entry| {
+            let song = if entry.path.is_file() && is_audio_file(&entry.path) {
+                read_metadata(&entry.path).ok()
+            } else {
+                None
+            };
+            song.ok_or_else(|| MissingPlaylistEntry {
+                path: entry.path.to_string_lossy().to_string(),
+                title: entry.title,
+            })
+        })
+        .collect();
+
+    let mut import = PlaylistImport::default();
+    for result in results {
+        match result {
+            Ok(song) => import.songs.push(song),
+            Err(missing) => import.missing.push(missing),
+        }
+    }
+    Ok(import)
+}
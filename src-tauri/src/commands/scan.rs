@@ -15,8 +15,7 @@ use crate::db::{self, DbState, SongInput};
 use crate::models::{
     LocalScanOptions, ScanMode, ScanPhase, ScanProgress, ScanResult, StreamScanOptions,
 };
-use crate::utils::audio::{is_audio_file, read_metadata_with_mtime};
-use crate::utils::cover::extract_and_cache_cover;
+use crate::utils::audio::{is_audio_file, read_metadata_with, MetadataOptions};
 
 /// Emit scan progress event
 fn emit_progress(app: &AppHandle, progress: &ScanProgress) {
@@ -35,8 +34,12 @@ pub async fn scan_local_to_db(
     let min_duration = options.min_duration.unwrap_or(0.0);
     let batch_size = options.batch_size;
 
-    // Get cover cache for use in parallel processing
-    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone_arc();
+    // Cache covers while reading metadata (one parse per file, no inline base64)
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let metadata_options = MetadataOptions {
+        cover_cache: Some(cache),
+        ..Default::default()
+    };
 
     // Phase 1: Collect all audio file paths
     emit_progress(
@@ -157,12 +160,12 @@ pub async fn scan_local_to_db(
 
     let processed_count = Arc::new(AtomicUsize::new(0));
     let error_count = Arc::new(AtomicUsize::new(0));
-    let cache_clone = cache.clone();
 
     let songs: Vec<SongInput> = files_to_scan
         .par_iter()
         .filter_map(|path| {
-            let result = read_metadata_with_mtime(path);
+            // 只打开一次文件，元数据和封面一起读取
+            let result = read_metadata_with(path, &metadata_options);
             let processed = processed_count.fetch_add(1, Ordering::Relaxed) + 1;
 
             // Emit progress every 50 files
@@ -187,9 +190,6 @@ pub async fn scan_local_to_db(
                         return None;
                     }
 
-                    // Extract and cache cover, get hash
-                    let cover_hash = extract_and_cache_cover(path, &cache_clone).ok().flatten();
-
                     Some(SongInput {
                         id: song.id,
                         title: song.title,
@@ -200,10 +200,14 @@ pub async fn scan_local_to_db(
                         file_size: song.file_size as i64,
                         is_hr: song.is_hr,
                         is_sq: song.is_sq,
-                        cover_hash, // Store hash instead of base64
+                        cover_hash: song.cover_hash, // Store hash instead of base64
                         server_song_id: None,
                         stream_info: None,
-                        file_modified: Some(song.file_modified),
+                        file_modified: song.file_modified,
+                        album_artist: Some(song.album_artists.join(" / "))
+                            .filter(|a| !a.is_empty()),
+                        musicbrainz_album_id: song.musicbrainz_album_id,
+                        compilation: song.compilation,
                     })
                 }
                 Err(_) => {
@@ -467,6 +471,9 @@ pub async fn scan_stream_to_db(
                     }
                 }).to_string()),
                 file_modified: None,
+                album_artist: None,
+                musicbrainz_album_id: None,
+                compilation: false,
             })
             .collect();
 
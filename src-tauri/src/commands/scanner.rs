@@ -1,11 +1,70 @@
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::fs;
-use walkdir::WalkDir;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use walkdir::{DirEntry, WalkDir};
+use encoding_rs::Encoding;
 use rayon::prelude::*;
 use serde::Serialize;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, Manager, State};
 
-use crate::models::{ScanOptions, ScannedSong};
-use crate::utils::audio::{is_audio_file, read_lyrics, read_metadata};
+use crate::commands::{parse_cover_size, CoverCacheState};
+use crate::db::{self, CachedMetadata, DbState};
+use crate::models::{
+    MusicScanResult, ScanBatch, ScanError, ScanJobFinished, ScanJobStatus, ScanOptions, ScanPhase,
+    ScanProgress, ScannedSong,
+};
+use crate::utils::cover::{CoverCache, CoverSize};
+use crate::utils::audio::{
+    canonical_path_key, has_extension, is_audio_file, normalize_extensions, read_lyrics,
+    read_metadata, read_metadata_from_bytes, read_metadata_with, resolve_path, song_id,
+    MetadataOptions,
+};
+use crate::utils::{archive, cue, mojibake};
+use crate::utils::sort::pinyin_cmp;
+use crate::utils::lyrics::{parse_lyric_lines, LyricLine};
+
+/// 已结束但一直未被 poll_scan 取走的后台任务结果的保留时间
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// 扫描进度事件的发送间隔（每处理多少个文件发送一次）
+const PROGRESS_INTERVAL: usize = 100;
+
+/// 发送扫描进度事件（前端订阅 "scan-progress"）
+fn emit_progress(app: &AppHandle, phase: ScanPhase, total: usize, processed: usize, errors: usize) {
+    let _ = app.emit(
+        "scan-progress",
+        ScanProgress {
+            phase,
+            total,
+            processed,
+            current_file: None,
+            skipped: 0,
+            errors,
+        },
+    );
+}
+
+/// 扫描取消标志（Tauri 托管状态）
+#[derive(Default)]
+pub struct ScanCancelState(pub Arc<AtomicBool>);
+
+/// 后台扫描任务（Tauri 托管状态）：任务 ID 计数器与各任务的状态
+#[derive(Default)]
+pub struct ScanJobsState {
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<u64, ScanJob>>,
+}
+
+/// 一个后台扫描任务：独立的取消标志、状态与结束时间
+struct ScanJob {
+    cancelled: Arc<AtomicBool>,
+    status: ScanJobStatus,
+    finished_at: Option<Instant>,
+}
 
 /// 目录项
 #[derive(Debug, Serialize)]
@@ -13,11 +72,48 @@ pub struct DirectoryEntry {
     pub name: String,
     pub path: String,
     pub is_dir: bool,
+    /// 是否为音频文件（目录恒为 false）
+    pub is_audio: bool,
+    /// 文件大小（字节），目录为 None
+    pub size: Option<u64>,
+    /// 修改时间（Unix 毫秒）
+    pub modified_ms: Option<u64>,
+    /// 目录下（不递归）的音频文件数，仅在请求时统计
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio_count: Option<usize>,
 }
 
-/// 列出目录内容（仅目录）
+/// 读取修改时间（Unix 毫秒）
+fn modified_ms(metadata: &fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
+
+/// 统计目录下（不递归）的音频文件数
+fn count_audio_files(dir: &Path) -> usize {
+    fs::read_dir(dir)
+        .map(|read_dir| {
+            read_dir
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_ok_and(|t| !t.is_dir()))
+                .filter(|e| is_audio_file(&e.path()))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// 列出目录内容（默认仅目录，`include_files` 为 true 时也包含文件）
+/// `include_audio_count` 为 true 时并行统计每个子目录下的音频文件数
 #[tauri::command]
-pub fn list_directories(path: String) -> Result<Vec<DirectoryEntry>, String> {
+pub fn list_directories(
+    path: String,
+    include_audio_count: Option<bool>,
+    include_files: Option<bool>,
+) -> Result<Vec<DirectoryEntry>, String> {
+    let include_files = include_files.unwrap_or(false);
     let dir_path = Path::new(&path);
 
     if !dir_path.exists() {
@@ -34,24 +130,34 @@ pub fn list_directories(path: String) -> Result<Vec<DirectoryEntry>, String> {
         Ok(read_dir) => {
             for entry in read_dir.filter_map(|e| e.ok()) {
                 let entry_path = entry.path();
-                // 只返回目录
-                if entry_path.is_dir() {
-                    let name = entry_path
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default();
-
-                    // 跳过隐藏目录
-                    if name.starts_with('.') {
-                        continue;
-                    }
+                // 跟随符号链接读取元数据
+                let Ok(metadata) = fs::metadata(&entry_path) else {
+                    continue;
+                };
+                let is_dir = metadata.is_dir();
+                if !is_dir && !include_files {
+                    continue;
+                }
 
-                    entries.push(DirectoryEntry {
-                        name,
-                        path: entry_path.to_string_lossy().to_string(),
-                        is_dir: true,
-                    });
+                let name = entry_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                // 跳过隐藏项
+                if name.starts_with('.') {
+                    continue;
                 }
+
+                entries.push(DirectoryEntry {
+                    name,
+                    path: entry_path.to_string_lossy().to_string(),
+                    is_dir,
+                    is_audio: !is_dir && is_audio_file(&entry_path),
+                    size: (!is_dir).then_some(metadata.len()),
+                    modified_ms: modified_ms(&metadata),
+                    audio_count: None,
+                });
             }
         }
         Err(e) => {
@@ -59,86 +165,676 @@ pub fn list_directories(path: String) -> Result<Vec<DirectoryEntry>, String> {
         }
     }
 
-    // 按名称排序
-    entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    // 目录在前，再按名称自然排序（中文按拼音）
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| pinyin_cmp(&a.name, &b.name))
+    });
+
+    if include_audio_count.unwrap_or(false) {
+        entries.par_iter_mut().filter(|entry| entry.is_dir).for_each(|entry| {
+            entry.audio_count = Some(count_audio_files(Path::new(&entry.path)));
+        });
+    }
 
     Ok(entries)
 }
 
-/// 扫描指定目录中的音乐文件
+/// 判断文件自上次扫描后是否未修改（用于增量扫描）
+/// `key` 为记录修改时间所用的路径（压缩包内文件为虚拟路径，修改时间取压缩包的）
+fn is_unchanged(entry: &DirEntry, key: &Path, known_mtimes: &HashMap<String, i64>) -> bool {
+    let Some(known) = known_mtimes.get(key.to_string_lossy().as_ref()) else {
+        return false;
+    };
+
+    entry
+        .metadata()
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64 == *known)
+        .unwrap_or(false)
+}
+
+/// 记录已访问的目录（规范化路径），用于打破符号链接造成的循环
+/// 已访问过或无法解析时返回 false
+fn first_visit(path: &Path, visited: &mut HashSet<PathBuf>) -> bool {
+    match fs::canonicalize(path) {
+        Ok(canonical) => visited.insert(canonical),
+        Err(_) => false,
+    }
+}
+
+/// 文件收集阶段的共享设置
+struct CollectConfig<'a> {
+    /// 自定义扩展名列表（已规范化），None 时使用内置判断
+    extensions: Option<Vec<String>>,
+    /// 增量扫描时已知的文件修改时间
+    known_mtimes: Option<&'a HashMap<String, i64>>,
+    follow_links: bool,
+    max_depth: Option<usize>,
+    /// 是否扫描压缩包内的音频文件
+    scan_archives: bool,
+    /// 跨目录共享的已访问目录集合（仅在跟随符号链接时使用）
+    visited_dirs: Mutex<HashSet<PathBuf>>,
+    cancelled: &'a AtomicBool,
+}
+
+impl CollectConfig<'_> {
+    /// 判断文件是否需要扫描
+    fn is_wanted(&self, path: &Path) -> bool {
+        match &self.extensions {
+            Some(list) => has_extension(path, list),
+            None => is_audio_file(path),
+        }
+    }
+}
+
+/// 遍历单个目录，收集音频文件路径以及遍历中遇到的错误
+fn collect_dir(dir: &str, config: &CollectConfig) -> (Vec<PathBuf>, Vec<ScanError>) {
+    let mut paths = Vec::new();
+    let mut errors = Vec::new();
+
+    let dir_path = Path::new(dir);
+    if !dir_path.exists() {
+        return (paths, errors);
+    }
+
+    let mut walker = WalkDir::new(dir_path).follow_links(config.follow_links);
+    if let Some(depth) = config.max_depth {
+        walker = walker.max_depth(depth);
+    }
+
+    for entry in walker.into_iter().filter_entry(|e| {
+        if !config.follow_links || !e.file_type().is_dir() {
+            return true;
+        }
+        match config.visited_dirs.lock() {
+            Ok(mut visited) => first_visit(e.path(), &mut visited),
+            Err(_) => false,
+        }
+    }) {
+        if config.cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push(ScanError {
+                    path: e
+                        .path()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_else(|| dir.to_string()),
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let path = entry.path();
+        if path.is_file() && config.is_wanted(path) {
+            // 增量模式下跳过未修改的文件，只返回新增/变更的歌曲
+            if config.known_mtimes.is_some_and(|known| is_unchanged(&entry, path, known)) {
+                continue;
+            }
+            paths.push(path.to_path_buf());
+        } else if config.scan_archives && path.is_file() && archive::is_archive(path) {
+            match archive::list_entries(path, |name| config.is_wanted(name)) {
+                Ok(entries) => paths.extend(
+                    entries
+                        .iter()
+                        .map(|name| archive::virtual_path(path, name))
+                        .filter(|virtual_path| {
+                            !config
+                                .known_mtimes
+                                .is_some_and(|known| is_unchanged(&entry, virtual_path, known))
+                        }),
+                ),
+                Err(message) => errors.push(ScanError {
+                    path: path.to_string_lossy().to_string(),
+                    message,
+                }),
+            }
+        }
+    }
+
+    (paths, errors)
+}
+
+/// 扫描指定目录中的音乐文件，扫描在阻塞线程池中进行，
+/// 期间主线程仍可处理 cancel_scan，进度与 "scan-batch" 事件实时送达
+#[tauri::command]
+pub async fn scan_music_files(
+    app: AppHandle,
+    cancel: State<'_, ScanCancelState>,
+    cover_cache: State<'_, CoverCacheState>,
+    options: ScanOptions,
+) -> Result<MusicScanResult, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let cancelled = Arc::clone(&cancel.0);
+    tauri::async_runtime::spawn_blocking(move || run_scan(&app, &cancelled, cache, options, None))
+        .await
+        .map_err(|e| format!("扫描任务异常退出: {}", e))?
+}
+
+/// 流式扫描：每解析完一首歌曲立即通过 `on_song` 通道发送给前端，Rust 端不保留
+/// 完整的歌曲列表，超大曲库也只占用少量内存。返回值中 songs 为空，只包含失败的文件
 #[tauri::command]
-pub fn scan_music_files(options: ScanOptions) -> Result<Vec<ScannedSong>, String> {
+pub async fn scan_music_files_streamed(
+    app: AppHandle,
+    cancel: State<'_, ScanCancelState>,
+    cover_cache: State<'_, CoverCacheState>,
+    options: ScanOptions,
+    on_song: Channel<ScannedSong>,
+) -> Result<MusicScanResult, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let cancelled = Arc::clone(&cancel.0);
+    tauri::async_runtime::spawn_blocking(move || {
+        run_scan(&app, &cancelled, cache, options, Some(&on_song))
+    })
+    .await
+    .map_err(|e| format!("扫描任务异常退出: {}", e))?
+}
+
+/// 在后台线程中扫描，立即返回任务 ID
+/// 完成后发送 "scan-job-finished" 事件，结果通过 poll_scan 获取；
+/// 每个任务有独立的取消标志，用 cancel_scan(job_id) 取消
+/// 结束后超过 FINISHED_JOB_TTL 仍未取走的结果会被丢弃
+#[tauri::command]
+pub fn start_scan(
+    app: AppHandle,
+    cover_cache: State<'_, CoverCacheState>,
+    jobs: State<'_, ScanJobsState>,
+    options: ScanOptions,
+) -> Result<u64, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let job_id = jobs.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    {
+        let mut jobs = jobs.jobs.lock().map_err(|e| e.to_string())?;
+        jobs.retain(|_, job| job.finished_at.is_none_or(|at| at.elapsed() < FINISHED_JOB_TTL));
+        jobs.insert(
+            job_id,
+            ScanJob {
+                cancelled: Arc::clone(&cancelled),
+                status: ScanJobStatus::Running,
+                finished_at: None,
+            },
+        );
+    }
+
+    std::thread::spawn(move || {
+        let status = match run_scan(&app, &cancelled, cache, options, None) {
+            Ok(result) => ScanJobStatus::Done { result },
+            Err(error) => ScanJobStatus::Failed { error },
+        };
+        let success = matches!(status, ScanJobStatus::Done { .. });
+
+        let jobs = app.state::<ScanJobsState>();
+        if let Ok(mut jobs) = jobs.jobs.lock() {
+            if let Some(job) = jobs.get_mut(&job_id) {
+                job.status = status;
+                job.finished_at = Some(Instant::now());
+            }
+        }
+        let _ = app.emit("scan-job-finished", ScanJobFinished { job_id, success });
+    });
+
+    Ok(job_id)
+}
+
+/// 查询后台扫描任务的状态；已结束的任务在返回结果后移除
+#[tauri::command]
+pub fn poll_scan(jobs: State<'_, ScanJobsState>, job_id: u64) -> Result<ScanJobStatus, String> {
+    let mut jobs = jobs.jobs.lock().map_err(|e| e.to_string())?;
+    match jobs.get(&job_id) {
+        Some(job) if job.finished_at.is_none() => Ok(ScanJobStatus::Running),
+        Some(_) => Ok(jobs
+            .remove(&job_id)
+            .map(|job| job.status)
+            .unwrap_or(ScanJobStatus::Running)),
+        None => Err(format!("Unknown scan job: {}", job_id)),
+    }
+}
+
+/// 影响解析结果的扫描选项的指纹，选项不同时元数据缓存条目视为失效
+/// `separators` 为实际生效的分隔符，默认值变化时旧条目同样失效
+fn metadata_options_key(options: &ScanOptions, separators: &[String]) -> String {
+    let key = format!(
+        "{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+        separators,
+        options.audio_hash.unwrap_or(false),
+        options.analyze_audio.unwrap_or(false),
+        options.lame_gapless.unwrap_or(false),
+        options.legacy_encoding,
+        options.cache_covers.unwrap_or(false),
+        options.min_original_dimension,
+        options.cover_sizes,
+    );
+    format!("{:x}", md5::compute(key))
+}
+
+/// 执行一次扫描（scan_music_files、scan_music_files_streamed 与 start_scan 共用）
+/// 传入 `channel` 时歌曲逐首发送到通道，不计入返回结果
+fn run_scan(
+    app: &AppHandle,
+    cancelled: &AtomicBool,
+    cover_cache: CoverCache,
+    options: ScanOptions,
+    channel: Option<&Channel<ScannedSong>>,
+) -> Result<MusicScanResult, String> {
+    cancelled.store(false, Ordering::Relaxed);
+
     let skip_short = options.skip_short_audio.unwrap_or(false);
     let min_duration = options.min_duration.unwrap_or(30.0);
+    let parse_cue = options.parse_cue.unwrap_or(false);
 
-    // 第一步：快速收集所有音频文件路径（单线程，I/O 受限但很快）
-    let mut audio_paths: Vec<PathBuf> = Vec::new();
+    let mut metadata_options = MetadataOptions::default();
+    if let Some(separators) = &options.separators {
+        metadata_options.separators = separators.clone();
+    }
+    metadata_options.audio_hash = options.audio_hash.unwrap_or(false);
+    metadata_options.analyze_audio = options.analyze_audio.unwrap_or(false);
+    metadata_options.lame_gapless = options.lame_gapless.unwrap_or(false);
+    if let Some(retries) = options.open_retries {
+        metadata_options.open_retries = retries;
+    }
+    if let Some(delay_ms) = options.retry_delay_ms {
+        metadata_options.retry_delay = Duration::from_millis(delay_ms);
+    }
+    if let Some(label) = &options.legacy_encoding {
+        metadata_options.legacy_encoding = Some(
+            mojibake::encoding_for_label(label).ok_or_else(|| format!("未知编码: {}", label))?,
+        );
+    }
+    if options.cache_covers.unwrap_or(false) {
+        let cover_cache = match options.min_original_dimension {
+            Some(min) => cover_cache.with_min_original_dimension(min),
+            None => cover_cache,
+        };
+        let cover_cache = match &options.cover_sizes {
+            Some(names) => {
+                let sizes: Vec<_> =
+                    names.iter().map(|name| parse_cover_size(Some(name))).collect();
+                cover_cache.with_generated_sizes(&sizes)
+            }
+            None => cover_cache,
+        };
+        metadata_options.cover_cache = Some(match options.cover_threads {
+            Some(threads) => cover_cache.with_pool(threads)?,
+            None => cover_cache,
+        });
+    }
 
-    for dir in &options.directories {
-        let dir_path = Path::new(dir);
-        if !dir_path.exists() {
-            continue;
+    let config = CollectConfig {
+        // 自定义扩展名列表覆盖内置的音频格式判断
+        extensions: options.extensions.as_deref().map(normalize_extensions),
+        known_mtimes: options
+            .known_mtimes
+            .as_ref()
+            .filter(|_| options.incremental.unwrap_or(false)),
+        follow_links: options.follow_symlinks.unwrap_or(false),
+        max_depth: options.max_depth,
+        scan_archives: options.scan_archives.unwrap_or(false),
+        visited_dirs: Mutex::new(HashSet::new()),
+        cancelled,
+    };
+
+    emit_progress(app, ScanPhase::Collecting, 0, 0, 0);
+
+    // 第一步：收集所有音频文件路径（各个目录并行遍历，适合多磁盘的多个库目录）
+    let collected: Vec<(Vec<PathBuf>, Vec<ScanError>)> = options
+        .directories
+        .par_iter()
+        .map(|dir| collect_dir(dir, &config))
+        .collect();
+
+    let mut audio_paths: Vec<PathBuf> = Vec::new();
+    let mut errors: Vec<ScanError> = Vec::new();
+    // 完整遍历过的目录（存在且没有遍历错误），清理元数据缓存时只处理这些目录
+    let mut complete_dirs: Vec<&Path> = Vec::new();
+    for (dir, (paths, dir_errors)) in options.directories.iter().zip(collected) {
+        if Path::new(dir).is_dir() && dir_errors.is_empty() {
+            complete_dirs.push(Path::new(dir));
         }
+        audio_paths.extend(paths);
+        errors.extend(dir_errors);
+    }
+
+    // 重叠的扫描目录（如 /music 和 /music/rock）或符号链接会收集到同一文件，按规范化路径去重
+    let keys: Vec<String> = audio_paths.par_iter().map(|p| canonical_path_key(p)).collect();
+    let mut seen: HashSet<String> = HashSet::with_capacity(keys.len());
+    let mut keys = keys.into_iter();
+    audio_paths.retain(|_| keys.next().is_some_and(|key| seen.insert(key)));
+
+    let total = audio_paths.len();
+    emit_progress(app, ScanPhase::Scanning, total, 0, 0);
 
-        for entry in WalkDir::new(dir_path)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.is_file() && is_audio_file(path) {
-                audio_paths.push(path.to_path_buf());
+    // 元数据缓存：路径、修改时间、大小及解析选项均未变的文件直接使用缓存结果
+    let options_key = metadata_options_key(&options, &metadata_options.separators);
+    let metadata_cache: Option<HashMap<String, CachedMetadata>> =
+        if options.use_metadata_cache.unwrap_or(false) {
+            let db = app.state::<DbState>();
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            let cache = db::load_metadata_cache(&conn)
+                .map_err(|e| format!("读取元数据缓存失败: {}", e))?;
+            Some(cache)
+        } else {
+            None
+        };
+    let cache_updates: Mutex<Vec<(String, CachedMetadata)>> = Mutex::new(Vec::new());
+    let read_song = |path: &Path| -> Result<ScannedSong, String> {
+        let Some(cache) = &metadata_cache else {
+            return read_metadata_with(path, &metadata_options);
+        };
+        // 压缩包内的虚拟路径等无法获取文件信息的直接读取
+        let Some((mtime, size)) = fs::metadata(path).ok().and_then(|meta| {
+            let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+            Some((mtime as i64, meta.len() as i64))
+        }) else {
+            return read_metadata_with(path, &metadata_options);
+        };
+
+        let key = path.to_string_lossy().to_string();
+        let cached = cache
+            .get(&key)
+            .filter(|entry| {
+                entry.mtime == mtime && entry.size == size && entry.options_key == options_key
+            })
+            .and_then(|entry| serde_json::from_str::<ScannedSong>(&entry.song_json).ok());
+        if let Some(mut song) = cached {
+            // 封面地址不入缓存，由封面哈希重新生成（未开启封面缓存时不内联 base64）；
+            // 封面已被清除或淘汰时按未命中处理，重新解析以提取封面
+            let cover_url = match (&metadata_options.cover_cache, &song.cover_hash) {
+                (Some(cover_cache), Some(hash)) => {
+                    cover_cache.get_cover_url(hash, CoverSize::Mid).map(Some)
+                }
+                _ => Some(None),
+            };
+            if let Some(cover_url) = cover_url {
+                song.cover_url = cover_url;
+                return Ok(song);
             }
         }
-    }
+
+        let song = read_metadata_with(path, &metadata_options)?;
+        let stored = ScannedSong {
+            cover_url: None,
+            ..song.clone()
+        };
+        if let Ok(song_json) = serde_json::to_string(&stored) {
+            cache_updates.lock().unwrap_or_else(|e| e.into_inner()).push((
+                key,
+                CachedMetadata {
+                    mtime,
+                    size,
+                    options_key: options_key.clone(),
+                    song_json,
+                },
+            ));
+        }
+        Ok(song)
+    };
 
     // 第二步：并行读取元数据
-    let songs: Vec<ScannedSong> = audio_paths
+    let processed_count = AtomicUsize::new(0);
+    let error_count = AtomicUsize::new(0);
+
+    // 分批发送：攒够 batch_size 首歌曲即发送 "scan-batch" 事件
+    let batch_size = options.batch_size.filter(|n| *n > 0);
+    let batch: Mutex<Vec<ScannedSong>> = Mutex::new(Vec::new());
+    let send_batch = |songs: Vec<ScannedSong>| {
+        if !songs.is_empty() {
+            let _ = app.emit("scan-batch", ScanBatch { songs });
+        }
+    };
+    let add_to_batch = |file_songs: &[ScannedSong]| {
+        let Some(batch_size) = batch_size else {
+            return;
+        };
+        let full = {
+            let mut batch = batch.lock().unwrap_or_else(|e| e.into_inner());
+            batch.extend_from_slice(file_songs);
+            (batch.len() >= batch_size).then(|| std::mem::take(&mut *batch))
+        };
+        if let Some(songs) = full {
+            send_batch(songs);
+        }
+    };
+
+    let results: Vec<Result<Vec<ScannedSong>, ScanError>> = audio_paths
         .par_iter()
         .filter_map(|path| {
-            match read_metadata(path) {
+            // 已取消：跳过剩余文件，返回已扫描的部分
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            let result = read_song(path);
+
+            // 节流：每处理 PROGRESS_INTERVAL 个文件发送一次进度
+            let processed = processed_count.fetch_add(1, Ordering::Relaxed) + 1;
+            if processed.is_multiple_of(PROGRESS_INTERVAL) {
+                emit_progress(
+                    app,
+                    ScanPhase::Scanning,
+                    total,
+                    processed,
+                    error_count.load(Ordering::Relaxed),
+                );
+            }
+
+            match result {
                 Ok(song) => {
                     if skip_short && song.duration < min_duration {
-                        None
+                        return None;
+                    }
+                    let file_songs = if parse_cue {
+                        cue::split_by_cue(song, path, metadata_options.legacy_encoding)
                     } else {
-                        Some(song)
+                        vec![song]
+                    };
+                    if let Some(channel) = channel {
+                        for song in file_songs {
+                            let _ = channel.send(song);
+                        }
+                        return None;
                     }
+                    add_to_batch(&file_songs);
+                    Some(Ok(file_songs))
+                }
+                Err(message) => {
+                    error_count.fetch_add(1, Ordering::Relaxed);
+                    Some(Err(ScanError {
+                        path: path.to_string_lossy().to_string(),
+                        message,
+                    }))
                 }
-                Err(_) => None,
             }
         })
         .collect();
 
-    Ok(songs)
+    // 已删除文件的缓存条目：只清理完整遍历过的目录下未收集到的路径；
+    // 取消或增量扫描没有收集到全部文件，不清理
+    let stale_paths: Vec<String> = match &metadata_cache {
+        Some(cache) if !cancelled.load(Ordering::Relaxed) && config.known_mtimes.is_none() => {
+            let collected: HashSet<String> = audio_paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            cache
+                .keys()
+                .filter(|path| !collected.contains(*path))
+                .filter(|path| complete_dirs.iter().any(|dir| Path::new(path).starts_with(dir)))
+                .cloned()
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    // 写回新解析的文件（失败不影响本次扫描结果）
+    let cache_updates = cache_updates.into_inner().unwrap_or_else(|e| e.into_inner());
+    if !cache_updates.is_empty() || !stale_paths.is_empty() {
+        let db = app.state::<DbState>();
+        let saved = match db.0.lock() {
+            Ok(mut conn) => db::save_metadata_cache(&mut conn, &cache_updates)
+                .and_then(|_| db::delete_metadata_cache_entries(&mut conn, &stale_paths))
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = saved {
+            errors.push(ScanError {
+                path: String::new(),
+                message: format!("写入元数据缓存失败: {}", e),
+            });
+        }
+    }
+
+    // 发送剩余不足一批的歌曲
+    send_batch(batch.into_inner().unwrap_or_else(|e| e.into_inner()));
+
+    // 成功的歌曲照常返回，失败的文件汇总到 errors
+    let mut songs = Vec::with_capacity(results.len());
+    for result in results {
+        match result {
+            Ok(file_songs) => songs.extend(file_songs),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    emit_progress(
+        app,
+        ScanPhase::Complete,
+        total,
+        total,
+        errors.len(),
+    );
+
+    Ok(MusicScanResult { songs, errors })
+}
+
+/// 取消扫描：传入 job_id 时只取消该后台任务（start_scan），
+/// 否则取消 scan_music_files / scan_music_files_streamed
+#[tauri::command]
+pub fn cancel_scan(
+    cancel: State<'_, ScanCancelState>,
+    jobs: State<'_, ScanJobsState>,
+    job_id: Option<u64>,
+) -> Result<(), String> {
+    let Some(job_id) = job_id else {
+        cancel.0.store(true, Ordering::Relaxed);
+        return Ok(());
+    };
+
+    let jobs = jobs.jobs.lock().map_err(|e| e.to_string())?;
+    let job = jobs
+        .get(&job_id)
+        .ok_or_else(|| format!("Unknown scan job: {}", job_id))?;
+    job.cancelled.store(true, Ordering::Relaxed);
+    Ok(())
 }
 
 /// 获取单个音乐文件的元数据
+/// - 非音频文件：返回 None
+/// - 文件不存在或读取失败（如损坏的 FLAC）：返回错误信息
 #[tauri::command]
 pub fn get_music_metadata(file_path: String) -> Result<Option<ScannedSong>, String> {
-    let path = Path::new(&file_path);
+    let path = resolve_path(&file_path);
 
-    if !path.exists() || !path.is_file() {
-        return Ok(None);
+    // 压缩包内的文件（虚拟路径）由 read_metadata 从压缩包读取
+    if !path.is_file() && archive::split_virtual_path(&path).is_none() {
+        return Err(format!("文件不存在: {}", file_path));
     }
 
-    if !is_audio_file(path) {
+    if !is_audio_file(&path) {
         return Ok(None);
     }
 
-    match read_metadata(path) {
-        Ok(song) => Ok(Some(song)),
-        Err(_) => Ok(None),
-    }
+    read_metadata(&path).map(Some)
 }
 
-/// 获取歌曲歌词
+/// 批量获取音乐文件元数据（并行读取），结果与输入顺序一致
+/// 非音频文件或读取失败的文件对应 None
 #[tauri::command]
-pub fn get_lyrics(file_path: String) -> Result<Option<String>, String> {
-    let path = Path::new(&file_path);
+pub fn get_music_metadata_batch(paths: Vec<String>) -> Vec<(String, Option<ScannedSong>)> {
+    paths
+        .into_par_iter()
+        .map(|file_path| {
+            let path = resolve_path(&file_path);
+            let song = if path.is_file() && is_audio_file(&path) {
+                read_metadata(&path).ok()
+            } else {
+                None
+            };
+            (file_path, song)
+        })
+        .collect()
+}
+
+/// 从内存中的音频数据读取元数据（如流媒体已下载的音频），封面写入封面缓存
+/// `hint_ext` 为可选的扩展名提示，缺省时根据数据内容识别格式
+#[tauri::command]
+pub fn get_music_metadata_from_bytes(
+    cover_cache: State<'_, CoverCacheState>,
+    data: Vec<u8>,
+    hint_ext: Option<String>,
+) -> Result<ScannedSong, String> {
+    let cache = cover_cache.0.lock().map_err(|e| e.to_string())?.clone();
+    let options = MetadataOptions {
+        cover_cache: Some(cache),
+        ..Default::default()
+    };
+    read_metadata_from_bytes(&data, hint_ext.as_deref(), &options)
+}
+
+/// 计算文件对应的歌曲 ID（与扫描结果中的 id 一致），无需重新扫描即可查找单首歌曲
+#[tauri::command]
+pub fn get_song_id(file_path: String) -> String {
+    song_id(&resolve_path(&file_path))
+}
+
+/// 解析前端传入的本地编码名称（用于不是 UTF-8 的 .lrc 文件）
+fn lyrics_encoding(label: Option<&str>) -> Result<Option<&'static Encoding>, String> {
+    label
+        .map(|label| {
+            mojibake::encoding_for_label(label).ok_or_else(|| format!("未知编码: {}", label))
+        })
+        .transpose()
+}
+
+/// 获取歌曲歌词；`legacy_encoding` 与扫描选项相同，用于解码 GBK 等编码的 .lrc 文件
+#[tauri::command]
+pub fn get_lyrics(
+    file_path: String,
+    legacy_encoding: Option<String>,
+) -> Result<Option<String>, String> {
+    let path = resolve_path(&file_path);
+    let encoding = lyrics_encoding(legacy_encoding.as_deref())?;
 
     if !path.exists() || !path.is_file() {
         return Ok(None);
     }
 
-    Ok(read_lyrics(path))
+    Ok(read_lyrics(&path, encoding))
+}
+
+/// 获取解析后的歌词（外部 .lrc 或内嵌），按时间排序并展开多时间戳行
+#[tauri::command]
+pub fn get_parsed_lyrics(
+    file_path: String,
+    legacy_encoding: Option<String>,
+) -> Result<Vec<LyricLine>, String> {
+    let path = resolve_path(&file_path);
+    let encoding = lyrics_encoding(legacy_encoding.as_deref())?;
+
+    if !path.exists() || !path.is_file() {
+        return Ok(Vec::new());
+    }
+
+    Ok(read_lyrics(&path, encoding)
+        .map(|text| parse_lyric_lines(&text))
+        .unwrap_or_default())
 }
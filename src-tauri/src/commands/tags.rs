@@ -0,0 +1,62 @@
+//! 标签读写相关命令
+
+use std::path::Path;
+
+use tauri::State;
+
+use crate::commands::CoverCacheState;
+use crate::models::TagChanges;
+use crate::utils::cover::CoverSize;
+use crate::utils::tags;
+
+/// 读取指定键的标签值（如 MUSICBRAINZ_ALBUMID、ISRC、自定义 TXXX 帧），键名不区分大小写
+#[tauri::command]
+pub fn read_tag_field(file_path: String, key: String) -> Result<Option<String>, String> {
+    let path = Path::new(&file_path);
+
+    if !path.exists() || !path.is_file() {
+        return Ok(None);
+    }
+
+    tags::read_tag_field(path, &key)
+}
+
+/// 将编辑后的标签写回文件（只写入提供的字段）
+#[tauri::command]
+pub fn write_metadata(file_path: String, changes: TagChanges) -> Result<(), String> {
+    let path = Path::new(&file_path);
+
+    if !path.is_file() {
+        return Err(format!("文件不存在: {}", file_path));
+    }
+
+    tags::write_metadata(path, &changes)
+}
+
+/// 将封面缓存中的原图写入音频文件标签
+/// `picture_type` 默认为封面正面；`replace` 默认为 true，即先移除同类型的已有图片
+#[tauri::command]
+pub fn embed_cover(
+    cover_cache: State<'_, CoverCacheState>,
+    file_path: String,
+    cover_hash: String,
+    picture_type: Option<String>,
+    replace: Option<bool>,
+) -> Result<(), String> {
+    let path = Path::new(&file_path);
+
+    if !path.is_file() {
+        return Err(format!("文件不存在: {}", file_path));
+    }
+
+    let pic_type = tags::parse_picture_type(picture_type.as_deref())?;
+
+    let (data, mime) = {
+        let cache = cover_cache.0.lock().map_err(|e| e.to_string())?;
+        cache
+            .get_cover_bytes(&cover_hash, CoverSize::Original)
+            .ok_or_else(|| format!("封面原图不在缓存中: {}", cover_hash))?
+    };
+
+    tags::embed_picture(path, data, mime, pic_type, replace.unwrap_or(true))
+}
@@ -2,6 +2,9 @@
 
 use rusqlite::{Connection, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::utils::albums::{album_id, resolve_album_artist};
 
 /// Aggregated album data
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,42 +38,52 @@ fn extract_cover_url(stream_info: &Option<String>) -> Option<String> {
     })
 }
 
-/// Get all albums aggregated from songs
+/// Get all albums aggregated from songs.
+/// Songs are grouped like `group_into_albums` (MusicBrainz album ID, else
+/// album artist + album name, with compilations under "Various Artists"),
+/// so both return the same album IDs
 pub fn get_all_albums(conn: &Connection) -> Result<Vec<DbAlbum>> {
     let mut stmt = conn.prepare(
-        "SELECT
-            album,
-            MIN(artist) as artist,
-            MAX(cover_hash) as cover_hash,
-            MAX(stream_info) as stream_info,
-            COUNT(*) as song_count
+        "SELECT album, artist, album_artist, musicbrainz_album_id, cover_hash, stream_info,
+                compilation
          FROM songs
-         GROUP BY album
-         ORDER BY album COLLATE NOCASE"
+         ORDER BY album COLLATE NOCASE, artist COLLATE NOCASE"
     )?;
 
-    let albums = stmt.query_map([], |row| {
+    let mut albums: Vec<DbAlbum> = Vec::new();
+    let mut index_by_id: HashMap<String, usize> = HashMap::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
         let album_name: String = row.get(0)?;
         let artist: String = row.get(1)?;
-        let cover_hash: Option<String> = row.get(2)?;
-        let stream_info: Option<String> = row.get(3)?;
-        let song_count: i64 = row.get(4)?;
-
-        // Generate a stable ID from album name
-        let id = format!("album-{:x}", md5::compute(&album_name));
-
-        // Extract cover URL from stream_info JSON
-        let stream_cover_url = extract_cover_url(&stream_info);
-
-        Ok(DbAlbum {
-            id,
-            name: album_name,
-            artist,
-            cover_hash,
-            stream_cover_url,
-            song_count,
-        })
-    })?.collect::<Result<Vec<_>>>()?;
+        let album_artist: Option<String> = row.get(2)?;
+        let musicbrainz_album_id: Option<String> = row.get(3)?;
+        let cover_hash: Option<String> = row.get(4)?;
+        let stream_info: Option<String> = row.get(5)?;
+        let compilation: i32 = row.get(6)?;
+
+        let album_artist = resolve_album_artist(compilation != 0, album_artist.as_deref(), &artist);
+        let id = album_id(&album_artist, &album_name, musicbrainz_album_id.as_deref());
+        let index = *index_by_id.entry(id.clone()).or_insert_with(|| {
+            albums.push(DbAlbum {
+                id,
+                name: album_name,
+                artist: album_artist,
+                cover_hash: None,
+                stream_cover_url: None,
+                song_count: 0,
+            });
+            albums.len() - 1
+        });
+
+        let album = &mut albums[index];
+        album.song_count += 1;
+        album.cover_hash = album.cover_hash.take().max(cover_hash);
+        if album.stream_cover_url.is_none() {
+            // Extract cover URL from stream_info JSON
+            album.stream_cover_url = extract_cover_url(&stream_info);
+        }
+    }
 
     Ok(albums)
 }
@@ -3,7 +3,7 @@
 use rusqlite::{Connection, Result};
 use std::path::Path;
 
-const CURRENT_SCHEMA_VERSION: i32 = 2;
+const CURRENT_SCHEMA_VERSION: i32 = 7;
 
 /// Initialize the database with tables and indexes
 pub fn init_db(conn: &Connection) -> Result<()> {
@@ -37,6 +37,21 @@ fn run_migrations(conn: &Connection, from_version: i32) -> Result<()> {
     if from_version < 2 {
         migrate_v2(conn)?;
     }
+    if from_version < 3 {
+        migrate_v3(conn)?;
+    }
+    if from_version < 4 {
+        migrate_v4(conn)?;
+    }
+    if from_version < 5 {
+        migrate_v5(conn)?;
+    }
+    if from_version < 6 {
+        migrate_v6(conn)?;
+    }
+    if from_version < 7 {
+        migrate_v7(conn)?;
+    }
 
     Ok(())
 }
@@ -152,6 +167,79 @@ fn migrate_v2(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Version 3: Add metadata_cache table for skipping unchanged files in scans
+fn migrate_v3(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS metadata_cache (
+            path            TEXT PRIMARY KEY,
+            mtime           INTEGER NOT NULL,
+            size            INTEGER NOT NULL,
+            song_json       TEXT NOT NULL,
+            updated_at      INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+        )",
+        [],
+    )?;
+
+    // Record version
+    conn.execute("INSERT INTO schema_version (version) VALUES (?1)", [3])?;
+
+    Ok(())
+}
+
+/// Version 4: Key metadata_cache entries by the parse options that produced them
+fn migrate_v4(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE metadata_cache ADD COLUMN options_key TEXT NOT NULL DEFAULT ''",
+        [],
+    )?;
+
+    // Record version
+    conn.execute("INSERT INTO schema_version (version) VALUES (?1)", [4])?;
+
+    Ok(())
+}
+
+/// Version 5: Add cover_cache_configs table for persisted cover cache settings
+fn migrate_v5(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS cover_cache_configs (
+            id              INTEGER PRIMARY KEY,
+            config_json     TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Record version
+    conn.execute("INSERT INTO schema_version (version) VALUES (?1)", [5])?;
+
+    Ok(())
+}
+
+/// Version 6: Store the album artist and MusicBrainz album ID of songs, so
+/// albums aggregated from the database get the same IDs as `group_into_albums`
+fn migrate_v6(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE songs ADD COLUMN album_artist TEXT", [])?;
+    conn.execute("ALTER TABLE songs ADD COLUMN musicbrainz_album_id TEXT", [])?;
+
+    // Record version
+    conn.execute("INSERT INTO schema_version (version) VALUES (?1)", [6])?;
+
+    Ok(())
+}
+
+/// Version 7: Store the compilation flag so compilations group under "Various Artists"
+fn migrate_v7(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE songs ADD COLUMN compilation INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+
+    // Record version
+    conn.execute("INSERT INTO schema_version (version) VALUES (?1)", [7])?;
+
+    Ok(())
+}
+
 /// Open or create a database at the given path
 pub fn open_db(path: &Path) -> Result<Connection> {
     let conn = Connection::open(path)?;
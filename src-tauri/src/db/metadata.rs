@@ -0,0 +1,88 @@
+//! Persistent metadata cache for local scans
+//!
+//! Stores each file's parsed `ScannedSong` (as JSON) keyed by path, along with
+//! the file's modification time and size and a fingerprint of the parse
+//! options, so unchanged files don't need their tags read again.
+
+use rusqlite::{params, Connection, Result};
+use std::collections::HashMap;
+
+/// A cached metadata entry
+#[derive(Debug, Clone)]
+pub struct CachedMetadata {
+    pub mtime: i64,
+    pub size: i64,
+    /// Fingerprint of the scan options the entry was parsed with
+    pub options_key: String,
+    /// `ScannedSong` serialized as JSON (without `cover_url`)
+    pub song_json: String,
+}
+
+/// Load the whole metadata cache, keyed by file path
+pub fn load_metadata_cache(conn: &Connection) -> Result<HashMap<String, CachedMetadata>> {
+    let mut stmt =
+        conn.prepare("SELECT path, mtime, size, options_key, song_json FROM metadata_cache")?;
+    let entries = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                CachedMetadata {
+                    mtime: row.get(1)?,
+                    size: row.get(2)?,
+                    options_key: row.get(3)?,
+                    song_json: row.get(4)?,
+                },
+            ))
+        })?
+        .collect::<Result<HashMap<_, _>>>()?;
+    Ok(entries)
+}
+
+/// Insert or update cache entries (within a transaction)
+pub fn save_metadata_cache(
+    conn: &mut Connection,
+    entries: &[(String, CachedMetadata)],
+) -> Result<usize> {
+    let tx = conn.transaction()?;
+
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR REPLACE INTO metadata_cache
+                (path, mtime, size, options_key, song_json, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, strftime('%s','now'))",
+        )?;
+        for (path, entry) in entries {
+            stmt.execute(params![
+                path,
+                entry.mtime,
+                entry.size,
+                entry.options_key,
+                entry.song_json
+            ])?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(entries.len())
+}
+
+/// Delete the cache entries of the given paths (e.g. files that no longer exist)
+pub fn delete_metadata_cache_entries(conn: &mut Connection, paths: &[String]) -> Result<usize> {
+    let tx = conn.transaction()?;
+
+    let mut removed = 0;
+    {
+        let mut stmt = tx.prepare("DELETE FROM metadata_cache WHERE path = ?1")?;
+        for path in paths {
+            removed += stmt.execute(params![path])?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(removed)
+}
+
+/// Clear the metadata cache (e.g. after changing parse options)
+pub fn clear_metadata_cache(conn: &Connection) -> Result<usize> {
+    conn.execute("DELETE FROM metadata_cache", [])
+}
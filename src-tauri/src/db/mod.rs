@@ -7,6 +7,7 @@ pub mod init;
 pub mod songs;
 pub mod albums;
 pub mod servers;
+pub mod metadata;
 
 use rusqlite::Connection;
 use std::sync::Mutex;
@@ -15,6 +16,7 @@ pub use init::*;
 pub use songs::*;
 pub use albums::*;
 pub use servers::*;
+pub use metadata::*;
 
 /// Database state wrapper for Tauri managed state
 pub struct DbState(pub Mutex<Connection>);
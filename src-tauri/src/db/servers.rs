@@ -30,6 +30,8 @@ pub struct StreamServerInput {
     pub server_name: String,
     pub server_url: String,
     pub username: String,
+    /// May be empty for passwordless Emby/Jellyfin accounts
+    #[serde(default, deserialize_with = "crate::models::null_as_empty")]
     pub password: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub access_token: Option<String>,
@@ -48,6 +50,39 @@ pub struct ScanConfig {
     pub last_scan_at: Option<i64>,
 }
 
+/// Cover cache configuration, applied to the app-wide cover cache at startup.
+/// Unset fields keep the cache's built-in defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CoverCacheConfig {
+    /// Encoding of the small and mid variants ("jpeg" or "webp")
+    pub format: Option<String>,
+    pub small_size: Option<u32>,
+    pub mid_size: Option<u32>,
+    pub small_quality: Option<u8>,
+    pub mid_quality: Option<u8>,
+    /// How non-square art is fitted into variants ("fill" or "contain")
+    pub fit: Option<String>,
+    /// RGBA padding color for "contain" (unpadded when unset)
+    pub pad_color: Option<[u8; 4]>,
+    pub progressive: bool,
+    pub strip_metadata: bool,
+    pub perceptual_dedupe: bool,
+    /// Maximum total cache size in bytes (unbounded when unset)
+    pub max_bytes: Option<u64>,
+    pub folder_art_names: Option<Vec<String>>,
+    /// Worker threads dedicated to cover decoding/encoding
+    pub threads: Option<usize>,
+    pub min_original_dimension: Option<u32>,
+    /// Sizes to generate ("small", "mid", "orig"); all when unset
+    pub generated_sizes: Option<Vec<String>>,
+    /// Remove corrupt covers (e.g. left by an unclean shutdown) in the
+    /// background at startup; results arrive as a "cover-cache-verified" event
+    pub verify_on_startup: bool,
+    /// Extra cache roots searched after the primary directory, in order
+    pub fallback_roots: Option<Vec<String>>,
+}
+
 /// Generate a server ID from URL and username
 fn generate_server_id(server_url: &str, username: &str) -> String {
     let mut hasher = Sha256::new();
@@ -223,6 +258,35 @@ pub fn get_scan_config(conn: &Connection) -> Result<Option<ScanConfig>> {
     }
 }
 
+/// Save cover cache configuration
+pub fn save_cover_cache_config(conn: &Connection, config: &CoverCacheConfig) -> Result<()> {
+    let config_json = serde_json::to_string(config).unwrap_or_else(|_| "{}".to_string());
+
+    // We keep only one cover cache config, so delete and insert
+    conn.execute("DELETE FROM cover_cache_configs", [])?;
+    conn.execute(
+        "INSERT INTO cover_cache_configs (config_json) VALUES (?1)",
+        params![config_json],
+    )?;
+
+    Ok(())
+}
+
+/// Get cover cache configuration (defaults when none was saved)
+pub fn get_cover_cache_config(conn: &Connection) -> Result<CoverCacheConfig> {
+    let config_json = conn.query_row(
+        "SELECT config_json FROM cover_cache_configs LIMIT 1",
+        [],
+        |row| row.get::<_, String>(0),
+    );
+
+    match config_json {
+        Ok(json) => Ok(serde_json::from_str(&json).unwrap_or_default()),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(CoverCacheConfig::default()),
+        Err(e) => Err(e),
+    }
+}
+
 /// Update last scan timestamp
 #[allow(dead_code)]
 pub fn update_last_scan_time(conn: &Connection) -> Result<()> {
@@ -55,6 +55,14 @@ pub struct SongInput {
     pub stream_info: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_modified: Option<i64>,
+    /// Album artist tag(s), joined with " / "; the track artist is used when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_artist: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub musicbrainz_album_id: Option<String>,
+    /// Part of a compilation (grouped under "Various Artists")
+    #[serde(default)]
+    pub compilation: bool,
 }
 
 /// Get all songs from the database (fast loading, no cover data)
@@ -139,8 +147,10 @@ pub fn save_songs(
             "INSERT OR REPLACE INTO songs
              (id, title, artist, album, duration, file_path, file_size,
               is_hr, is_sq, cover_hash, source_type, server_id, server_song_id,
-              stream_info, file_modified, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, strftime('%s','now'))"
+              stream_info, file_modified, album_artist, musicbrainz_album_id, compilation,
+              updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                     ?18, strftime('%s','now'))"
         )?;
 
         for song in songs {
@@ -160,6 +170,9 @@ pub fn save_songs(
                 song.server_song_id,
                 song.stream_info,
                 song.file_modified,
+                song.album_artist,
+                song.musicbrainz_album_id,
+                if song.compilation { 1 } else { 0 },
             ])?;
         }
     }
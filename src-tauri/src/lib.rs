@@ -10,19 +10,31 @@ use commands::{
     db_delete_stream_server, db_get_all_albums, db_get_all_artists, db_get_all_songs,
     db_get_library_stats, db_get_scan_config, db_get_stream_servers,
     db_migrate_from_localstorage, db_save_scan_config, db_save_songs, db_save_stream_server,
-    fetch_stream_songs, fetch_subsonic_songs, get_lyrics, get_music_metadata, get_stream_lyrics,
+    db_clear_metadata_cache, open_library_db,
+    fetch_stream_songs, fetch_subsonic_songs, get_lyrics, get_parsed_lyrics, get_music_metadata, get_music_metadata_batch, get_music_metadata_from_bytes, get_song_id, read_tag_field, write_metadata, embed_cover, get_stream_lyrics,
     get_stream_url, get_subsonic_lyrics, get_subsonic_stream_url, jellyfin_authenticate,
-    list_directories, scan_music_files, test_stream_connection, test_subsonic_connection,
+    list_directories, scan_music_files, scan_music_files_streamed, start_scan, poll_scan,
+    cancel_scan, ScanCancelState, ScanJobsState, test_stream_connection, test_subsonic_connection,
     scan_local_to_db, scan_stream_to_db,
     // Cover cache commands
-    get_cover_url, get_cover_urls_batch, get_cover_cache_stats, cleanup_orphaned_covers, clear_cover_cache,
-    cleanup_missing_songs, CoverCacheState,
+    get_cover_url, get_cover_urls_batch, get_cover_data_url, export_cover, extract_covers_batch, extract_all_covers, get_cover_variants, regenerate_cover_variants, get_blurhash, get_cover_palette, get_cover_dominant_color, get_cover_sprite_sheet, cache_stream_cover, get_cover_cache_stats, set_cover_cache_fallback_roots, get_cover_cache_config, set_cover_cache_config, cleanup_orphaned_covers, verify_cover_cache, clear_cover_cache,
+    set_album_cover, clear_album_cover, get_album_cover_overrides,
+    cleanup_missing_songs, CoverCacheState, cover_protocol, apply_cover_cache_config,
+    // Playlist commands
+    export_playlist, import_playlist,
+    // Library commands
+    search_songs, sort_songs, pinyin_initials, index_letters, group_into_albums,
+    library_stats,
+    // File organization commands
+    move_song, delete_song,
+    // Audio CD commands
+    read_cd_toc,
     // File watcher commands
     start_file_watcher, stop_file_watcher,
     // Audio engine commands
     audio_play, audio_pause, audio_resume, audio_stop, audio_seek,
     audio_set_volume, audio_set_eq_bands, audio_set_eq_enabled,
-    audio_enable_visualization, audio_get_state,
+    audio_enable_visualization, audio_get_state, generate_waveform,
 };
 use db::DbState;
 use utils::cover::CoverCache;
@@ -66,10 +78,23 @@ pub fn run() {
     let builder = builder.plugin(tauri_plugin_window_state::Builder::default().build());
 
     builder
+        // 封面流式读取协议（支持 Range 请求）：cover://localhost/<hash>?size=orig
+        .register_uri_scheme_protocol("cover", cover_protocol)
         .invoke_handler(tauri::generate_handler![
             scan_music_files,
+            scan_music_files_streamed,
+            start_scan,
+            poll_scan,
+            cancel_scan,
             get_music_metadata,
+            get_music_metadata_batch,
+            get_music_metadata_from_bytes,
+            get_song_id,
             get_lyrics,
+            get_parsed_lyrics,
+            read_tag_field,
+            write_metadata,
+            embed_cover,
             list_directories,
             // 统一流媒体命令
             test_stream_connection,
@@ -98,16 +123,51 @@ pub fn run() {
             db_clear_scan_config,
             db_migrate_from_localstorage,
             db_get_library_stats,
+            db_clear_metadata_cache,
+            open_library_db,
             // 高级扫描命令
             scan_local_to_db,
             scan_stream_to_db,
             // 封面缓存命令
             get_cover_url,
             get_cover_urls_batch,
+            get_cover_data_url,
+            export_cover,
+            extract_covers_batch,
+            extract_all_covers,
+            get_cover_variants,
+            regenerate_cover_variants,
+            get_blurhash,
+            get_cover_palette,
+            get_cover_dominant_color,
+            get_cover_sprite_sheet,
+            cache_stream_cover,
+            set_album_cover,
+            clear_album_cover,
+            get_album_cover_overrides,
             get_cover_cache_stats,
+            set_cover_cache_fallback_roots,
+            get_cover_cache_config,
+            set_cover_cache_config,
             cleanup_orphaned_covers,
+            verify_cover_cache,
             clear_cover_cache,
             cleanup_missing_songs,
+            // 播放列表命令
+            export_playlist,
+            import_playlist,
+            // 资料库命令
+            search_songs,
+            sort_songs,
+            pinyin_initials,
+            index_letters,
+            group_into_albums,
+            library_stats,
+            // 文件整理命令
+            move_song,
+            delete_song,
+            // 音频 CD 命令
+            read_cd_toc,
             // 文件监听命令
             start_file_watcher,
             stop_file_watcher,
@@ -124,7 +184,8 @@ pub fn run() {
             audio_set_eq_bands,
             audio_set_eq_enabled,
             audio_enable_visualization,
-            audio_get_state
+            audio_get_state,
+            generate_waveform
         ])
         .on_window_event(|_window, _event| {
             #[cfg(desktop)]
@@ -145,18 +206,37 @@ pub fn run() {
 
             let db_path = app_data_dir.join("bayin.db");
             let conn = db::open_db(&db_path).expect("Failed to open database");
+            let cover_config = db::servers::get_cover_cache_config(&conn).unwrap_or_default();
 
             app.manage(DbState(Mutex::new(conn)));
 
+            // 初始化扫描取消标志
+            app.manage(ScanCancelState::default());
+            app.manage(ScanJobsState::default());
+
             // 初始化封面缓存
             let cache_dir = app
                 .path()
                 .app_cache_dir()
                 .expect("Failed to get app cache directory");
             let cover_cache_dir = cache_dir.join("covers");
+            // 应用保存的封面缓存设置；设置无效时使用默认值
             let cover_cache = CoverCache::new(cover_cache_dir);
+            let cover_cache = apply_cover_cache_config(cover_cache.clone(), &cover_config)
+                .unwrap_or(cover_cache);
             cover_cache.ensure_dirs().expect("Failed to create cover cache directories");
 
+            // 开启启动校验时，后台清理异常退出留下的损坏封面，
+            // 受影响的 hash 通过 cover-cache-verified 事件通知前端重新提取
+            if cover_config.verify_on_startup {
+                let cache = cover_cache.clone();
+                let app_handle = app.handle().clone();
+                std::thread::spawn(move || {
+                    let corrupt = cache.verify();
+                    let _ = app_handle.emit("cover-cache-verified", corrupt);
+                });
+            }
+
             app.manage(CoverCacheState(Mutex::new(cover_cache)));
 
             // 初始化文件监听器状态（仅桌面端）
@@ -356,6 +436,9 @@ pub fn run() {
                                                 server_song_id: None,
                                                 stream_info: None,
                                                 file_modified: Some(song.file_modified),
+                                                album_artist: song.album_artist,
+                                                musicbrainz_album_id: song.musicbrainz_album_id,
+                                                compilation: song.compilation,
                                             })
                                         }
                                         Err(_) => None,
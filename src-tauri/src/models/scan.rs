@@ -49,6 +49,26 @@ pub enum ScanPhase {
     Complete,
 }
 
+/// State of a background scan job started with `start_scan`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ScanJobStatus {
+    /// Still collecting or reading files
+    Running,
+    /// Finished (possibly cancelled early with partial results)
+    Done { result: super::MusicScanResult },
+    /// Failed before scanning started
+    Failed { error: String },
+}
+
+/// "scan-job-finished" event payload; fetch the result with `poll_scan`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanJobFinished {
+    pub job_id: u64,
+    pub success: bool,
+}
+
 /// Scan result summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -111,4 +131,7 @@ pub struct ScannedSongWithMtime {
     pub is_hr: Option<bool>,
     pub is_sq: Option<bool>,
     pub file_modified: i64,
+    pub album_artist: Option<String>,
+    pub musicbrainz_album_id: Option<String>,
+    pub compilation: bool,
 }
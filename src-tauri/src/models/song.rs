@@ -1,7 +1,9 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
 
 /// 扫描到的歌曲信息，与前端 ScannedSong 接口一一对应
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ScannedSong {
     pub id: String,
@@ -17,6 +19,163 @@ pub struct ScannedSong {
     pub is_hr: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_sq: Option<bool>,
+    /// 文件修改时间（Unix 秒），供下次增量扫描使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_modified: Option<i64>,
+    /// 加入曲库的时间（Unix 毫秒）：取文件创建时间，不支持时退回修改时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_added_ms: Option<u64>,
+    /// 内嵌歌词（LYRICS / USLT 标签）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lyrics: Option<String>,
+    /// 内嵌歌词是否为带时间戳的同步歌词（LRC 格式）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lyrics_synced: Option<bool>,
+    /// 音轨增益（dB，ReplayGain 优先，R128 作为后备）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_gain_db: Option<f32>,
+    /// 专辑增益（dB，ReplayGain 优先，R128 作为后备）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_gain_db: Option<f32>,
+    /// 码率（kbps）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bitrate: Option<u32>,
+    /// 采样率（Hz）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_rate: Option<u32>,
+    /// 位深（有损格式通常没有）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bit_depth: Option<u8>,
+    /// 声道数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels: Option<u8>,
+    /// 全部艺术家（多值标签拆分后），`artist` 为其拼接结果
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artists: Vec<String>,
+    /// 全部专辑艺术家
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub album_artists: Vec<String>,
+    /// 全部流派
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub genres: Vec<String>,
+    /// 全部作曲家
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub composers: Vec<String>,
+    /// 音轨号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_no: Option<u32>,
+    /// 音轨总数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_total: Option<u32>,
+    /// 碟号（本地文件缺失时为 1）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disc_no: Option<u32>,
+    /// 碟片总数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disc_total: Option<u32>,
+    /// 音频流内容哈希（不含标签），用于查找重复歌曲；需在扫描选项中开启
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_hash: Option<String>,
+    /// 封面缓存哈希（扫描时开启 cache_covers 才会填充）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_hash: Option<String>,
+    /// 文件名不是有效 UTF-8 时 file_path 会被有损转换，此时提供字节精确的
+    /// file:// URL，调用命令时应传回该值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_url: Option<String>,
+    /// CUE 拆分的音轨在整轨文件中的开始位置（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_ms: Option<u64>,
+    /// CUE 拆分的音轨结束位置（毫秒），最后一轨为 None（播放到文件末尾）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_ms: Option<u64>,
+    /// 艺术家排序名（ARTISTSORT）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist_sort: Option<String>,
+    /// 专辑艺术家排序名（ALBUMARTISTSORT）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_artist_sort: Option<String>,
+    /// 专辑排序名（ALBUMSORT）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_sort: Option<String>,
+    /// 发行日期（标签原值，优先原始发行日期），如 "2019-03-01"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// 从发行日期解析出的年份，用于按年份排序
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<u16>,
+    /// MusicBrainz 专辑（发行）ID，用于区分同名专辑
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub musicbrainz_album_id: Option<String>,
+    /// 合辑（COMPILATION / TCMP 标签），分组时归入 "Various Artists"
+    #[serde(default)]
+    pub compilation: bool,
+    /// 每分钟节拍数（BPM / TBPM 标签）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bpm: Option<f32>,
+    /// 调性（INITIALKEY / TKEY 标签），统一为标准写法，如 "Am"、"F#"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// 编码器延迟（样本数，来自 iTunSMPB 或 LAME 信息头），无缝播放时裁掉开头
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoder_delay: Option<u32>,
+    /// 末尾补齐的样本数，无缝播放时裁掉结尾
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoder_padding: Option<u32>,
+    /// 章节（有声书 / 播客），没有章节时为空
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+    /// 元数据不完整：文件无法被完整解析，只有文件名和文件头中的基本信息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lossy: Option<bool>,
+    /// 采样峰值（线性，1.0 为 0 dBFS），开启 analyze_audio 时计算
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak: Option<f32>,
+    /// 满幅采样的比例（0 ~ 1），比例高说明削波严重
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clip_ratio: Option<f32>,
+    /// 乱码标签已按该编码修复（如 "GBK"），便于提示用户写回标签
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repaired_encoding: Option<String>,
+    /// DSD 采样率（如 DSD64 为 2822400），仅 DSF / DFF 文件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dsd_rate: Option<u32>,
+    /// 编码格式标记，目前仅 DSD 文件为 "DSD"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+/// 章节
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    /// 开始时间（毫秒）
+    pub start_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// 扫描失败的文件及原因
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanError {
+    pub path: String,
+    pub message: String,
+}
+
+/// scan_music_files 的扫描结果：成功的歌曲与失败的文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MusicScanResult {
+    pub songs: Vec<ScannedSong>,
+    pub errors: Vec<ScanError>,
+}
+
+/// "scan-batch" 事件内容：扫描过程中提前发送的一批歌曲
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanBatch {
+    pub songs: Vec<ScannedSong>,
 }
 
 /// 扫描选项
@@ -28,4 +187,205 @@ pub struct ScanOptions {
     pub skip_short_audio: Option<bool>,
     #[serde(default)]
     pub min_duration: Option<f64>,
+    /// 增量扫描：跳过修改时间与 known_mtimes 中记录一致的文件
+    #[serde(default)]
+    pub incremental: Option<bool>,
+    /// 上次扫描得到的 文件路径 -> 修改时间（Unix 秒）
+    #[serde(default)]
+    pub known_mtimes: Option<HashMap<String, i64>>,
+    /// 自定义音频扩展名列表（不区分大小写，可带前导点），为空时使用内置列表
+    #[serde(default)]
+    pub extensions: Option<Vec<String>>,
+    /// 是否跟随符号链接（默认 false，开启时会检测循环链接）
+    #[serde(default)]
+    pub follow_symlinks: Option<bool>,
+    /// 最大递归深度（目录本身为 0）
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// 多值标签的后备分隔符（默认只有 `;`，`/` 需显式传入），传空列表则只按空字符拆分
+    #[serde(default)]
+    pub separators: Option<Vec<String>>,
+    /// 计算音频内容哈希（较慢，默认关闭）
+    #[serde(default)]
+    pub audio_hash: Option<bool>,
+    /// 解码每个文件统计峰值与削波比例（较慢，默认关闭）
+    #[serde(default)]
+    pub analyze_audio: Option<bool>,
+    /// 没有 iTunSMPB 的 MP3 读取 LAME 信息头中的编码器延迟与补齐
+    /// （每个 MP3 需再次打开文件，默认关闭）
+    #[serde(default)]
+    pub lame_gapless: Option<bool>,
+    /// 使用数据库中的元数据缓存：路径、修改时间和大小都未变的文件直接返回缓存结果，
+    /// 不再读取标签（更改解析选项后需先清空缓存）
+    #[serde(default)]
+    pub use_metadata_cache: Option<bool>,
+    /// 扫描时同时缓存封面并填充 cover_hash（cover_url 改为缓存地址而非 base64）
+    #[serde(default)]
+    pub cache_covers: Option<bool>,
+    /// 封面解码/编码使用的线程数上限（默认与扫描共用全部核心）
+    #[serde(default)]
+    pub cover_threads: Option<usize>,
+    /// 封面短边小于该像素数时不缓存（如 64x64 的内嵌缩略图），改用目录中的 folder.jpg 等
+    #[serde(default)]
+    pub min_original_dimension: Option<u32>,
+    /// 只生成这些尺寸的封面（"small"、"mid"、"orig"），默认三种都生成
+    #[serde(default)]
+    pub cover_sizes: Option<Vec<String>>,
+    /// 打开文件遇到 I/O 错误（如 SMB 共享上的网络中断）时的重试次数，默认 2
+    #[serde(default)]
+    pub open_retries: Option<u32>,
+    /// 首次重试前的等待毫秒数，之后每次翻倍，默认 200
+    #[serde(default)]
+    pub retry_delay_ms: Option<u64>,
+    /// 按同名 .cue 文件把整轨专辑拆分为多首歌曲（会改变歌曲数量）
+    #[serde(default)]
+    pub parse_cue: Option<bool>,
+    /// 同时扫描 zip 压缩包内的音频文件（路径形如 "专辑.zip!/01.flac"）
+    #[serde(default)]
+    pub scan_archives: Option<bool>,
+    /// 设置后每读取到这么多首歌曲就通过 "scan-batch" 事件提前发送，
+    /// 前端可边扫描边显示（最终结果仍包含全部歌曲）
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// 修复乱码标签时使用的本地编码（"gbk"、"shift_jis"、"big5" 等），不设置则不检测
+    #[serde(default)]
+    pub legacy_encoding: Option<String>,
+}
+
+/// 标签编辑内容：只写入提供的字段，其余标签保持不变
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagChanges {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub track_no: Option<u32>,
+    #[serde(default)]
+    pub track_total: Option<u32>,
+}
+
+/// 播放列表导出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistFormat {
+    /// 扩展 M3U（UTF-8，不带 BOM）
+    M3u,
+    /// M3U8（UTF-8，带 BOM）
+    M3u8,
+}
+
+/// 播放列表中已不存在（或无法读取）的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingPlaylistEntry {
+    pub path: String,
+    /// 播放列表中记录的标题（#EXTINF / TitleN），供界面提示
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// 播放列表导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistImport {
+    /// 按播放列表顺序读取到的歌曲
+    pub songs: Vec<ScannedSong>,
+    /// 缺失的条目
+    pub missing: Vec<MissingPlaylistEntry>,
+}
+
+/// move_song 的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedSong {
+    /// 移动前的歌曲 ID
+    pub old_id: String,
+    /// 按新路径生成的歌曲 ID
+    pub id: String,
+    /// 新的文件路径
+    pub file_path: String,
+    /// 封面哈希（按内容寻址，移动后不变）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_hash: Option<String>,
+    /// 同名歌词文件移动失败的原因（歌曲本身已移动）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lyrics_error: Option<String>,
+}
+
+/// delete_song 的结果：歌曲文件已删除，附带的清理步骤失败时给出原因
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedSong {
+    /// 从数据库中移除的歌曲数
+    pub removed_rows: usize,
+    /// 同名歌词文件删除失败的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lyrics_error: Option<String>,
+    /// 清理无引用封面失败的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_error: Option<String>,
+}
+
+/// 音频 CD 上的一条音轨，位置以帧（1/75 秒）为单位，不含开头 150 帧的引导区
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CdTrack {
+    pub number: u8,
+    pub start_frame: u32,
+    pub length_frames: u32,
+}
+
+/// 音频 CD 的目录（TOC）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CdToc {
+    pub tracks: Vec<CdTrack>,
+    /// 音频部分结尾（导出区）的位置
+    pub lead_out_frame: u32,
+    /// MusicBrainz 光盘 ID，可用于查询专辑信息
+    pub musicbrainz_disc_id: String,
+}
+
+/// 由扫描结果分组得到的专辑
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Album {
+    /// 由分组键生成的稳定 ID
+    pub id: String,
+    pub name: String,
+    pub album_artist: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<u16>,
+    /// 代表封面（第一首有封面的歌曲）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub musicbrainz_album_id: Option<String>,
+    /// 专辑内歌曲数
+    pub track_count: usize,
+    /// 专辑内歌曲在输入列表中的下标（按碟号、音轨号排序）
+    pub song_indices: Vec<usize>,
+}
+
+/// 资料库统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryStats {
+    pub total_tracks: usize,
+    /// 总时长（秒）
+    pub total_duration: f64,
+    /// 总文件大小（字节）
+    pub total_size: u64,
+    /// 按格式（小写扩展名）统计的歌曲数
+    pub by_format: BTreeMap<String, usize>,
+    /// 按码率区间统计的歌曲数（如 "128-191"、"320-999"、"1000+"、"unknown"）
+    pub by_bitrate: BTreeMap<String, usize>,
+    /// 高解析度（采样率高于 44.1kHz 或位深高于 16bit）歌曲数
+    pub hi_res_tracks: usize,
+    /// 无损格式歌曲数
+    pub lossless_tracks: usize,
 }
@@ -1,7 +1,7 @@
 //! 流媒体服务器数据模型（支持 Navidrome/Subsonic/Jellyfin/Emby 等）
 #![allow(dead_code)]
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// 服务器类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -23,6 +23,8 @@ pub struct StreamServerConfig {
     pub server_name: String,
     pub server_url: String,
     pub username: String,
+    /// 密码（Emby/Jellyfin 本地账户可以为空）
+    #[serde(default, deserialize_with = "null_as_empty")]
     pub password: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub access_token: Option<String>,
@@ -30,6 +32,14 @@ pub struct StreamServerConfig {
     pub user_id: Option<String>,
 }
 
+/// 将 null 反序列化为空字符串（前端对空密码可能传 null）
+pub fn null_as_empty<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 impl StreamServerConfig {
     /// 是否使用 Subsonic API（Navidrome/Subsonic/OpenSubsonic）
     pub fn is_subsonic(&self) -> bool {
@@ -0,0 +1,80 @@
+//! 将扫描到的歌曲按专辑分组
+
+use std::collections::HashMap;
+
+use crate::models::{Album, ScannedSong};
+use crate::utils::search::fold;
+use crate::utils::sort::song_cmp;
+
+/// 合辑的专辑艺术家
+const VARIOUS_ARTISTS: &str = "Various Artists";
+
+/// 专辑艺术家：合辑统一为 "Various Artists"，否则优先 ALBUMARTIST 标签，
+/// 缺失时使用音轨艺术家（数据库聚合专辑时同样按此规则）
+pub fn resolve_album_artist(compilation: bool, album_artist: Option<&str>, artist: &str) -> String {
+    if compilation {
+        VARIOUS_ARTISTS.to_string()
+    } else {
+        album_artist.filter(|a| !a.is_empty()).unwrap_or(artist).to_string()
+    }
+}
+
+fn album_artist(song: &ScannedSong) -> String {
+    let album_artists = song.album_artists.join(" / ");
+    resolve_album_artist(song.compilation, Some(&album_artists), &song.artist)
+}
+
+/// 分组键：有 MusicBrainz 专辑 ID 时以其为准，否则按 (专辑艺术家, 专辑名)，
+/// 避免把不同艺术家的同名专辑（如 "Greatest Hits"）合并
+fn group_key(album_artist: &str, album: &str, musicbrainz_album_id: Option<&str>) -> String {
+    match musicbrainz_album_id {
+        Some(mbid) => format!("mb:{}", mbid.to_lowercase()),
+        None => format!("{}\u{0}{}", fold(album_artist), fold(album)),
+    }
+}
+
+/// 专辑 ID：分组键的哈希。group_into_albums 与 db_get_all_albums 共用，
+/// 用户设置的专辑封面按此 ID 关联
+pub fn album_id(album_artist: &str, album: &str, musicbrainz_album_id: Option<&str>) -> String {
+    let key = group_key(album_artist, album, musicbrainz_album_id);
+    format!("album-{:x}", md5::compute(key))
+}
+
+/// 按专辑分组，专辑按首次出现的顺序排列，专辑内歌曲按碟号、音轨号排序
+pub fn group_into_albums(songs: &[ScannedSong]) -> Vec<Album> {
+    let mut albums: Vec<Album> = Vec::new();
+    let mut index_by_key: HashMap<String, usize> = HashMap::new();
+
+    for (i, song) in songs.iter().enumerate() {
+        let artist = album_artist(song);
+        let id = album_id(&artist, &song.album, song.musicbrainz_album_id.as_deref());
+        let album_index = *index_by_key.entry(id.clone()).or_insert_with(|| {
+            albums.push(Album {
+                id,
+                name: song.album.clone(),
+                album_artist: artist,
+                musicbrainz_album_id: song.musicbrainz_album_id.clone(),
+                ..Default::default()
+            });
+            albums.len() - 1
+        });
+
+        let album = &mut albums[album_index];
+        album.song_indices.push(i);
+        // 年份取最早的一首，封面取第一首有封面的歌曲
+        album.year = match (album.year, song.year) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if album.cover_hash.is_none() {
+            album.cover_hash = song.cover_hash.clone();
+        }
+    }
+
+    for album in &mut albums {
+        album.song_indices.sort_by(|a, b| song_cmp(&songs[*a], &songs[*b]));
+        album.track_count = album.song_indices.len();
+    }
+
+    albums
+}
@@ -0,0 +1,45 @@
+//! 音频分析：解码整个文件，统计采样峰值与满幅采样比例，用于发现削波 / 过度压缩的母带
+
+use std::path::Path;
+
+use crate::audio_engine::decoder::AudioDecoder;
+
+/// 视为满幅的采样绝对值（整数格式转换为浮点后最大值略小于 1.0）
+const FULL_SCALE: f32 = 0.9999;
+
+/// 峰值分析结果
+#[derive(Debug, Clone, Copy)]
+pub struct PeakInfo {
+    /// 采样峰值（线性，1.0 为 0 dBFS）
+    pub peak: f32,
+    /// 满幅采样占全部采样的比例（0 ~ 1）
+    pub clip_ratio: f32,
+}
+
+/// 解码整个文件并统计峰值（耗时与解码一遍相当）
+pub fn analyze_peak(path: &Path) -> Result<PeakInfo, String> {
+    let source = path.to_str().ok_or("路径不是有效的 UTF-8")?;
+    let mut decoder = AudioDecoder::open(source)?;
+
+    let mut peak = 0.0f32;
+    let mut clipped = 0u64;
+    let mut total = 0u64;
+    while let Some(samples) = decoder.decode_next()? {
+        for sample in &samples {
+            let level = sample.abs();
+            peak = peak.max(level);
+            if level >= FULL_SCALE {
+                clipped += 1;
+            }
+        }
+        total += samples.len() as u64;
+    }
+
+    if total == 0 {
+        return Err("未解码到音频数据".to_string());
+    }
+    Ok(PeakInfo {
+        peak,
+        clip_ratio: clipped as f32 / total as f32,
+    })
+}
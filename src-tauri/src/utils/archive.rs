@@ -0,0 +1,85 @@
+//! 压缩包（zip）内音频文件的读取
+//!
+//! 压缩包内的文件用虚拟路径表示："专辑.zip!/Disc 1/01.flac"
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 压缩包路径与包内路径之间的分隔符
+const ARCHIVE_SEPARATOR: &str = "!/";
+
+/// 支持的压缩包扩展名
+const ARCHIVE_EXTENSIONS: &[&str] = &["zip"];
+
+/// 包内单个文件的最大解压长度（文件头中的大小不可信，只用于预分配）
+const MAX_ENTRY_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// 判断是否为支持的压缩包
+pub fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ARCHIVE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// 拼接压缩包内文件的虚拟路径
+pub fn virtual_path(archive: &Path, entry: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}{}", archive.to_string_lossy(), ARCHIVE_SEPARATOR, entry))
+}
+
+/// 拆分虚拟路径为 (压缩包路径, 包内路径)；不是虚拟路径时返回 None
+pub fn split_virtual_path(path: &Path) -> Option<(PathBuf, String)> {
+    let path_str = path.to_str()?;
+    // 只转换 ASCII，保证与原字符串的字节下标一致
+    let lower = path_str.to_ascii_lowercase();
+    ARCHIVE_EXTENSIONS.iter().find_map(|ext| {
+        let marker = format!(".{}{}", ext, ARCHIVE_SEPARATOR);
+        // 包内路径可能含有 "!/"，因此以第一个压缩包扩展名为准
+        let pos = lower.find(&marker)? + 1 + ext.len();
+        let entry = &path_str[pos + ARCHIVE_SEPARATOR.len()..];
+        Some((PathBuf::from(&path_str[..pos]), entry.to_string()))
+    })
+}
+
+fn open_archive(archive: &Path) -> Result<zip::ZipArchive<File>, String> {
+    let file = File::open(archive).map_err(|e| format!("无法打开压缩包: {}", e))?;
+    zip::ZipArchive::new(file).map_err(|e| format!("无法读取压缩包: {}", e))
+}
+
+/// 列出压缩包内满足条件的文件（包内路径）
+pub fn list_entries(
+    archive: &Path,
+    wanted: impl Fn(&Path) -> bool,
+) -> Result<Vec<String>, String> {
+    let mut zip = open_archive(archive)?;
+    let mut entries = Vec::new();
+    for i in 0..zip.len() {
+        let Ok(file) = zip.by_index(i) else {
+            continue;
+        };
+        if !file.is_dir() && wanted(Path::new(file.name())) {
+            entries.push(file.name().to_string());
+        }
+    }
+    Ok(entries)
+}
+
+/// 读取压缩包内文件的全部内容
+pub fn read_entry(archive: &Path, entry: &str) -> Result<Vec<u8>, String> {
+    let mut zip = open_archive(archive)?;
+    let mut file = zip
+        .by_name(entry)
+        .map_err(|e| format!("压缩包内找不到文件 {}: {}", entry, e))?;
+    if file.size() > MAX_ENTRY_SIZE {
+        return Err(format!("压缩包内文件过大: {}", entry));
+    }
+    let mut data = Vec::with_capacity(file.size() as usize);
+    file.by_ref()
+        .take(MAX_ENTRY_SIZE + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("无法解压文件 {}: {}", entry, e))?;
+    if data.len() as u64 > MAX_ENTRY_SIZE {
+        return Err(format!("压缩包内文件过大: {}", entry));
+    }
+    Ok(data)
+}
@@ -1,12 +1,28 @@
-use std::path::Path;
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
-use lofty::file::AudioFile;
+use encoding_rs::Encoding;
+use lofty::error::{ErrorKind, LoftyError};
+use lofty::file::{AudioFile, FileType, TaggedFile};
 use lofty::prelude::*;
 use lofty::probe::Probe;
+use lofty::tag::{ItemKey, ItemValue, Tag};
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, CONTROLS};
+use sha2::{Digest, Sha256};
+use symphonia::core::codecs::CODEC_TYPE_NULL;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::probe::Hint;
 
 use crate::models::{ScannedSong, ScannedSongWithMtime};
+use crate::utils::cover::{self, CoverCache, CoverSize};
+use crate::utils::{
+    analysis, archive, chapters, dsd, gapless, lyrics, mojibake, musical_key, pcm,
+};
 
 /// 支持的音频文件扩展名
 const AUDIO_EXTENSIONS: &[&str] = &[
@@ -16,6 +32,61 @@ const AUDIO_EXTENSIONS: &[&str] = &[
 /// 无损音频格式扩展名
 const LOSSLESS_EXTENSIONS: &[&str] = &["flac", "wav", "ape", "aiff", "dsf", "dff"];
 
+/// file:// URL 中需要转义的字符（非 ASCII 字节总会被转义）
+const FILE_URL_ESCAPE: &AsciiSet = &CONTROLS.add(b' ').add(b'%').add(b'#').add(b'?');
+
+/// 多值标签的默认后备分隔符（标签未使用空字符分隔多个值时）。
+/// 不含 `/`：AC/DC、Simon/Garfunkel 之类的名字会被误拆，需要时由扫描选项开启
+const DEFAULT_SEPARATORS: &[&str] = &[";"];
+
+/// 拼接多个艺术家时使用的分隔符
+const JOIN_SEPARATOR: &str = " / ";
+
+/// 计算音频哈希时最多读取的音频数据量（字节）
+const AUDIO_HASH_BYTES: usize = 1024 * 1024;
+
+/// 打开文件遇到 I/O 错误时的默认重试次数
+const DEFAULT_OPEN_RETRIES: u32 = 2;
+
+/// 首次重试前的默认等待时间
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// 元数据读取选项
+#[derive(Clone)]
+pub struct MetadataOptions {
+    /// 多值标签（艺术家、流派等）的后备分隔符
+    pub separators: Vec<String>,
+    /// 是否计算音频内容哈希
+    pub audio_hash: bool,
+    /// 设置后在读取元数据的同时缓存封面（复用同一次解析，不再重复打开文件）
+    pub cover_cache: Option<CoverCache>,
+    /// 打开文件遇到 I/O 错误时的重试次数（SMB / NAS 上的偶发网络错误）
+    pub open_retries: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    pub retry_delay: Duration,
+    /// 设置后按该编码修复乱码标签（GBK 等本地编码被当作 Latin-1 读出）
+    pub legacy_encoding: Option<&'static Encoding>,
+    /// 是否解码整个文件统计峰值与削波比例（耗时较长）
+    pub analyze_audio: bool,
+    /// 没有 iTunSMPB 的 MP3 是否读取 LAME 信息头中的无缝播放信息（需再次打开文件）
+    pub lame_gapless: bool,
+}
+
+impl Default for MetadataOptions {
+    fn default() -> Self {
+        Self {
+            separators: DEFAULT_SEPARATORS.iter().map(|s| s.to_string()).collect(),
+            audio_hash: false,
+            cover_cache: None,
+            open_retries: DEFAULT_OPEN_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            legacy_encoding: None,
+            analyze_audio: false,
+            lame_gapless: false,
+        }
+    }
+}
+
 /// 判断文件是否为音频文件
 pub fn is_audio_file(path: &Path) -> bool {
     path.extension()
@@ -24,6 +95,23 @@ pub fn is_audio_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// 规范化扩展名列表：去除前导点并转为小写
+pub fn normalize_extensions(extensions: &[String]) -> Vec<String> {
+    extensions
+        .iter()
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+/// 判断文件扩展名是否在（已规范化的）列表中
+pub fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| extensions.contains(&ext.to_lowercase()))
+        .unwrap_or(false)
+}
+
 /// 判断是否为无损格式
 fn is_lossless_format(path: &Path) -> bool {
     path.extension()
@@ -35,8 +123,7 @@ fn is_lossless_format(path: &Path) -> bool {
 /// 从文件路径提取文件名（不含扩展名）
 fn extract_filename(path: &Path) -> String {
     path.file_stem()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_string())
+        .map(|s| s.to_string_lossy().to_string())
         .unwrap_or_else(|| "未知标题".to_string())
 }
 
@@ -54,101 +141,817 @@ pub fn extract_filename_from_path_str(path_str: &str) -> Option<String> {
         .filter(|s| !s.is_empty())
 }
 
-/// 读取歌词（优先从外部 .lrc 文件，其次从音频文件内嵌歌词）
-pub fn read_lyrics(audio_path: &Path) -> Option<String> {
-    // 1. 尝试读取外部 .lrc 文件
-    let lrc_path = audio_path.with_extension("lrc");
-    if lrc_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&lrc_path) {
-            return Some(content);
+/// 计算歌曲的稳定 ID：文件路径的哈希
+/// 同一路径在重启、重新扫描后保持不变，文件移动后随之改变
+/// （沿用 md5，与数据库中已保存的歌曲 ID 保持一致）
+pub fn song_id(path: &Path) -> String {
+    format!("{:x}", md5::compute(path.to_string_lossy().as_bytes()))
+}
+
+/// 路径的原始字节（Unix 上不经过 UTF-8 转换；其他平台按 UTF-8 近似）
+pub fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Cow::Borrowed(path.as_os_str().as_bytes())
+    }
+    #[cfg(not(unix))]
+    {
+        Cow::Owned(path.to_string_lossy().replace('\\', "/").into_bytes())
+    }
+}
+
+/// 非 UTF-8 路径（如 Linux 上的 Latin-1 文件名）的字节精确表示：file:// URL。
+/// 有效 UTF-8 的路径返回 None（直接使用路径字符串即可）
+pub fn file_url(path: &Path) -> Option<String> {
+    if path.to_str().is_some() {
+        return None;
+    }
+    Some(format!("file://{}", percent_encode(&path_bytes(path), FILE_URL_ESCAPE)))
+}
+
+/// 路径去重用的规范化键：解析符号链接和 ".."，在大小写不敏感的文件系统
+/// （Windows / macOS 默认）上转为小写。压缩包内的虚拟路径只规范化压缩包部分，
+/// 无法解析的路径（如已删除的文件）按原样使用
+pub fn canonical_path_key(path: &Path) -> String {
+    let canonical = match archive::split_virtual_path(path).filter(|_| !path.exists()) {
+        Some((archive_path, entry)) => {
+            let archive_path = std::fs::canonicalize(&archive_path).unwrap_or(archive_path);
+            archive::virtual_path(&archive_path, &entry)
         }
+        None => std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
     }
+    .to_string_lossy()
+    .to_string();
+
+    if cfg!(any(windows, target_os = "macos")) {
+        canonical.to_lowercase()
+    } else {
+        canonical
+    }
+}
+
+/// 解析前端传入的路径：普通路径字符串，或 `file_url` 生成的 file:// URL
+pub fn resolve_path(path_str: &str) -> PathBuf {
+    let Some(encoded) = path_str.strip_prefix("file://") else {
+        return PathBuf::from(path_str);
+    };
+    let bytes: Vec<u8> = percent_decode_str(encoded).collect();
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        PathBuf::from(std::ffi::OsString::from_vec(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(&bytes).to_string())
+    }
+}
 
-    // 2. 尝试从音频文件读取内嵌歌词
-    if let Ok(tagged_file) = Probe::open(audio_path).and_then(|p| p.read()) {
-        if let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) {
-            // 尝试获取 LYRICS 标签（不同格式可能有不同的标签名）
-            // lofty 使用 ItemKey::Lyrics 来获取歌词
-            if let Some(lyrics) = tag.get_string(&lofty::tag::ItemKey::Lyrics) {
-                return Some(lyrics.to_string());
+/// 从标签中读取内嵌歌词
+fn embedded_lyrics(tag: &Tag) -> Option<String> {
+    // lofty 使用 ItemKey::Lyrics 来获取歌词（不同格式可能有不同的标签名，如 LYRICS / USLT）
+    tag.get_string(&ItemKey::Lyrics)
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.to_string())
+}
+
+/// 拆分多值标签：先按空字符（Vorbis 多个注释 / ID3v2.4 多值帧），
+/// 再按后备分隔符拆分，去除空白和重复项并保持原有顺序
+pub fn split_multi_value<'a>(
+    values: impl IntoIterator<Item = &'a str>,
+    separators: &[String],
+) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for value in values {
+        let mut parts: Vec<&str> = value.split('\0').collect();
+        for sep in separators.iter().filter(|s| !s.is_empty()) {
+            parts = parts.iter().flat_map(|p| p.split(sep.as_str())).collect();
+        }
+        for part in parts {
+            let part = part.trim();
+            if !part.is_empty() && !result.iter().any(|r| r == part) {
+                result.push(part.to_string());
             }
         }
     }
+    result
+}
 
-    None
+/// 读取某个键的全部值（同一键可能有多个帧/注释）
+fn read_multi_value(tag: Option<&Tag>, key: &ItemKey, separators: &[String]) -> Vec<String> {
+    tag.map(|t| split_multi_value(t.get_strings(key), separators))
+        .unwrap_or_default()
+}
+
+/// 读取单值文本标签，空值视为缺失
+fn read_text(tag: Option<&Tag>, key: &ItemKey) -> Option<String> {
+    tag.and_then(|t| t.get_string(key))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// 读取布尔标记（合辑等）：文本值为 "1" / "true"；MP4 的 cpil 是布尔原子，
+/// 按原子的值判断（非 0 即真），不能只按文本读取
+fn read_flag(tag: Option<&Tag>, key: &ItemKey) -> bool {
+    tag.and_then(|t| t.get(key)).is_some_and(|item| match item.value() {
+        ItemValue::Text(value) => {
+            let value = value.trim();
+            value == "1" || value.eq_ignore_ascii_case("true")
+        }
+        ItemValue::Binary(data) => data.iter().any(|b| *b != 0),
+        ItemValue::Locator(_) => false,
+    })
+}
+
+/// 从日期字符串中取出年份：第一段恰好 4 位的数字
+/// （"2019-03-01"、"2019/03"、"2019"、"01.03.2019" 均得到 2019）
+fn parse_year(value: &str) -> Option<u16> {
+    value
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|part| part.len() == 4)
+        .and_then(|part| part.parse().ok())
+        .filter(|year| *year > 0)
+}
+
+/// 读取发行日期，返回 (完整日期, 年份)
+/// 优先原始发行日期（再版专辑按首发年份排序），其次录音日期（ID3v2.4 TDRC）
+/// 和年份（ID3v2.3 TYER）
+fn read_date(tag: Option<&Tag>) -> (Option<String>, Option<u16>) {
+    let keys = [
+        ItemKey::OriginalReleaseDate,
+        // ID3v2.3 的原始年份帧及 Vorbis 常见写法，lofty 没有对应的通用键
+        ItemKey::Unknown("TORY".to_string()),
+        ItemKey::Unknown("ORIGINALYEAR".to_string()),
+        ItemKey::RecordingDate,
+        ItemKey::Year,
+        ItemKey::ReleaseDate,
+    ];
+
+    let dates: Vec<String> = keys.iter().filter_map(|key| read_text(tag, key)).collect();
+    let year = dates
+        .iter()
+        .find_map(|date| parse_year(date))
+        .or_else(|| tag.and_then(|t| t.year()).and_then(|y| u16::try_from(y).ok()));
+    (dates.into_iter().next(), year)
+}
+
+/// 读取 BPM：ID3v2 的 TBPM 为整数，Vorbis / APE 的 BPM 可能带小数（"128.00"、"127,5"）
+fn read_bpm(tag: Option<&Tag>) -> Option<f32> {
+    [ItemKey::Bpm, ItemKey::IntegerBpm]
+        .iter()
+        .filter_map(|key| read_text(tag, key))
+        .find_map(|value| value.replace(',', ".").parse::<f32>().ok())
+        .filter(|bpm| bpm.is_finite() && *bpm > 0.0 && *bpm < 1000.0)
+}
+
+/// 解析编号字段（如 "03"、"3/12"、" 3 / 12 "），返回 (编号, 总数)
+/// 前导零按十进制处理，0 视为缺失
+fn parse_number_pair(value: &str) -> (Option<u32>, Option<u32>) {
+    let parse = |s: &str| s.trim().parse::<u32>().ok().filter(|n| *n > 0);
+    match value.split_once('/') {
+        Some((no, total)) => (parse(no), parse(total)),
+        None => (parse(value), None),
+    }
+}
+
+/// 读取编号及总数：优先解析原始字符串，总数缺失时再查单独的 TOTAL 标签
+fn read_number_pair(
+    tag: Option<&Tag>,
+    number_key: ItemKey,
+    total_key: ItemKey,
+) -> (Option<u32>, Option<u32>) {
+    let Some(tag) = tag else {
+        return (None, None);
+    };
+    let (number, total) = tag
+        .get_string(&number_key)
+        .map(parse_number_pair)
+        .unwrap_or((None, None));
+    let total = total.or_else(|| {
+        tag.get_string(&total_key)
+            .and_then(|t| parse_number_pair(t).0)
+    });
+    (number, total)
+}
+
+/// 解析 ReplayGain 增益值（如 "+1.23 dB" / "-6.54 dB"）
+fn parse_gain_db(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let number = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .or_else(|| value.strip_suffix("DB"))
+        .unwrap_or(value);
+    number.trim().parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+/// 解析 R128 增益（Q7.8 定点整数，参考 -23 LUFS），
+/// 换算为 ReplayGain（参考 -18 LUFS）等效的 dB 值
+fn parse_r128_gain(value: &str) -> Option<f32> {
+    value.trim().parse::<i16>().ok().map(|q| q as f32 / 256.0 + 5.0)
+}
+
+/// 读取增益标签：ReplayGain 优先，R128 作为后备
+fn read_gain(tag: &Tag, replay_gain: ItemKey, r128_key: &str) -> Option<f32> {
+    tag.get_string(&replay_gain)
+        .and_then(parse_gain_db)
+        .or_else(|| {
+            tag.get_string(&ItemKey::Unknown(r128_key.to_string()))
+                .and_then(parse_r128_gain)
+        })
+}
+
+/// 计算音频内容哈希：对解封装后的前 AUDIO_HASH_BYTES 字节音频包数据做 SHA-256，
+/// 不包含标签和封面，因此音频相同、标签不同的文件哈希一致
+pub fn compute_audio_hash(path: &Path) -> Result<String, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    hash_audio_stream(mss, path.extension().and_then(|e| e.to_str()))
+}
+
+/// 对音频流的解封装数据计算哈希（文件与内存数据共用）
+fn hash_audio_stream(mss: MediaSourceStream, ext: Option<&str>) -> Result<String, String> {
+    let mut hint = Hint::new();
+    if let Some(ext) = ext {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &symphonia::core::meta::MetadataOptions::default(),
+        )
+        .map_err(|e| format!("无法识别音频格式: {}", e))?;
+    let mut reader = probed.format;
+
+    let track_id = reader
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .map(|t| t.id)
+        .ok_or("未找到音频轨道")?;
+
+    let mut hasher = Sha256::new();
+    let mut hashed = 0;
+    while hashed < AUDIO_HASH_BYTES {
+        // 读到文件末尾（或遇到无法解析的数据）时结束
+        let Ok(packet) = reader.next_packet() else {
+            break;
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let data = &packet.data[..packet.data.len().min(AUDIO_HASH_BYTES - hashed)];
+        hasher.update(data);
+        hashed += data.len();
+    }
+
+    if hashed == 0 {
+        return Err("未读取到音频数据".to_string());
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 读取歌词（优先从外部 .lrc 文件，其次从音频文件内嵌歌词）
+/// `legacy_encoding` 用于解码不是 UTF-8 的 .lrc 文件（GBK、Big5 等）
+pub fn read_lyrics(
+    audio_path: &Path,
+    legacy_encoding: Option<&'static Encoding>,
+) -> Option<String> {
+    // 1. 尝试读取外部 .lrc 文件（同目录同名，通常比内嵌歌词的时间轴更准）
+    if let Some(content) = lyrics::read_sidecar(audio_path, legacy_encoding) {
+        return Some(content);
+    }
+
+    // 2. 没有外部歌词时才解析音频文件读取内嵌歌词
+    let tagged_file = Probe::open(audio_path).and_then(|p| p.read()).ok()?;
+    primary_or_first_tag(&tagged_file).and_then(embedded_lyrics)
+}
+
+/// 获取主标签，没有时取第一个标签
+fn primary_or_first_tag(tagged_file: &TaggedFile) -> Option<&Tag> {
+    tagged_file.primary_tag().or_else(|| tagged_file.first_tag())
+}
+
+/// 按选项打开并解析音频文件：暂时性的 I/O 错误（网络中断、超时等）等待后重试，
+/// 文件不存在、内容损坏或格式解析失败则立即返回
+fn open_tagged_file_with(path: &Path, options: &MetadataOptions) -> Result<TaggedFile, String> {
+    let mut delay = options.retry_delay;
+    let mut attempt = 0;
+    loop {
+        let result = match Probe::open(path) {
+            Ok(probe) => probe
+                .read()
+                .map_err(|e| (is_transient(&e), format!("无法读取音频文件: {}", e))),
+            Err(e) => Err((is_transient(&e), format!("无法打开文件: {}", e))),
+        };
+        match result {
+            Ok(tagged_file) => return Ok(tagged_file),
+            Err((true, _)) if attempt < options.open_retries => {
+                attempt += 1;
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            Err((_, message)) if attempt > 0 => {
+                return Err(format!("{}（已重试 {} 次）", message, attempt))
+            }
+            Err((_, message)) => return Err(message),
+        }
+    }
+}
+
+/// 判断 lofty 错误是否为暂时性的 I/O 错误（值得重试）
+/// SMB / NAS 断线表现为 ConnectionReset、StaleNetworkFileHandle、EIO 等多种错误，
+/// 因此除文件不存在、无权限、截断或损坏（UnexpectedEof、InvalidData）等确定性错误外
+/// 一律重试；标签解析错误不重试
+fn is_transient(error: &LoftyError) -> bool {
+    match error.kind() {
+        ErrorKind::Io(e) => !matches!(
+            e.kind(),
+            std::io::ErrorKind::NotFound
+                | std::io::ErrorKind::PermissionDenied
+                | std::io::ErrorKind::UnexpectedEof
+                | std::io::ErrorKind::InvalidData
+                | std::io::ErrorKind::Unsupported
+        ),
+        _ => false,
+    }
 }
 
 /// 读取音频文件元数据
 pub fn read_metadata(path: &Path) -> Result<ScannedSong, String> {
+    read_metadata_with(path, &MetadataOptions::default())
+}
+
+/// 按指定选项读取音频文件元数据
+/// 压缩包内的文件使用虚拟路径（"专辑.zip!/01.flac"）
+pub fn read_metadata_with(path: &Path, options: &MetadataOptions) -> Result<ScannedSong, String> {
+    if !path.exists() {
+        if let Some((archive_path, entry)) = archive::split_virtual_path(path) {
+            return read_archive_metadata(path, &archive_path, &entry, options);
+        }
+    }
+
+    // lofty 不支持 DSD，单独解析文件头
+    if is_dsd_file(path) {
+        return read_dsd_metadata(path, options);
+    }
+
+    let tagged_file = match open_tagged_file_with(path, options) {
+        Ok(tagged_file) => tagged_file,
+        // lofty 无法解析的 WAV / AIFF 仍返回基本信息，而不是从扫描结果中消失
+        Err(e) => return fallback_pcm_song(path).ok_or(e),
+    };
+    song_from_tagged(path, &tagged_file, options)
+}
+
+/// 判断是否为 DSD 文件（DSF / DFF）
+fn is_dsd_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dsf") || ext.eq_ignore_ascii_case("dff"))
+}
+
+/// 读取 DSD 文件的元数据：时长、采样率等取自文件头，标签仍由 lofty 解析 ID3
+fn read_dsd_metadata(path: &Path, options: &MetadataOptions) -> Result<ScannedSong, String> {
+    let info = dsd::read_dsd_info(path).ok_or_else(|| "无法解析 DSD 文件头".to_string())?;
+    let tagged_file = open_tagged_bytes(&dsd::id3_as_mpeg(info.id3_tag.as_deref()), Some("mp3"))?;
+
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| format!("无法获取文件信息: {}", e))?;
+    let file_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+
+    let mut song = build_song(
+        &tagged_file,
+        SongSource {
+            path: Some(path),
+            id: song_id(path),
+            file_path: path.to_string_lossy().to_string(),
+            file_size: metadata.len(),
+            file_modified,
+            date_added_ms: date_added_ms(&metadata),
+            is_sq: true,
+        },
+        options,
+    );
+
+    // 音频属性以文件头为准（包装用的 MPEG 帧只是为了读取标签）
+    song.duration = info.duration;
+    song.sample_rate = Some(info.dsd_rate);
+    song.bit_depth = Some(1);
+    song.channels = Some(info.channels);
+    song.bitrate = Some((info.dsd_rate as u64 * info.channels as u64 / 1000) as u32);
+    song.is_hr = Some(true);
+    song.encoder_delay = None;
+    song.encoder_padding = None;
+    song.dsd_rate = Some(info.dsd_rate);
+    song.format = Some("DSD".to_string());
+    Ok(song)
+}
+
+/// 直接从 WAV / AIFF 文件头构建最简的 ScannedSong：标题取文件名，
+/// 并标记 lossy 表示元数据不完整
+fn fallback_pcm_song(path: &Path) -> Option<ScannedSong> {
+    let info = pcm::read_pcm_info(path)?;
+    let metadata = std::fs::metadata(path).ok()?;
+    let file_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+    let date_added_ms = date_added_ms(&metadata);
+    let is_hr = info.sample_rate.unwrap_or(0) > 44100 || info.bit_depth.is_some_and(|d| d > 16);
+
+    Some(ScannedSong {
+        id: song_id(path),
+        title: extract_filename(path),
+        artist: "未知艺术家".to_string(),
+        album: "未知专辑".to_string(),
+        duration: info.duration.unwrap_or(0.0),
+        file_path: path.to_string_lossy().to_string(),
+        file_size: metadata.len(),
+        is_hr: Some(is_hr),
+        is_sq: Some(true),
+        file_modified,
+        date_added_ms,
+        sample_rate: info.sample_rate,
+        bit_depth: info.bit_depth,
+        channels: info.channels,
+        disc_no: Some(1),
+        file_url: file_url(path),
+        lossy: Some(true),
+        ..Default::default()
+    })
+}
+
+/// 读取压缩包内音频文件的元数据：解压到内存后解析，修改时间取压缩包的修改时间
+fn read_archive_metadata(
+    path: &Path,
+    archive_path: &Path,
+    entry: &str,
+    options: &MetadataOptions,
+) -> Result<ScannedSong, String> {
+    let data = archive::read_entry(archive_path, entry)?;
+    let hint_ext = Path::new(entry).extension().and_then(|e| e.to_str());
+    let tagged_file = open_tagged_bytes(&data, hint_ext)?;
+
+    let archive_metadata = std::fs::metadata(archive_path).ok();
+    let file_modified = archive_metadata
+        .as_ref()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+
+    let mut song = build_song(
+        &tagged_file,
+        SongSource {
+            path: Some(path),
+            id: song_id(path),
+            file_path: path.to_string_lossy().to_string(),
+            file_size: data.len() as u64,
+            file_modified,
+            date_added_ms: archive_metadata.as_ref().and_then(date_added_ms),
+            is_sq: is_lossless_format(path),
+        },
+        options,
+    );
+
+    if options.audio_hash {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(data)), Default::default());
+        song.audio_hash = hash_audio_stream(mss, hint_ext).ok();
+    }
+
+    Ok(song)
+}
+
+/// 从内存中的音频数据读取元数据（如流媒体已下载的音频），无需本地路径
+/// `hint_ext` 为可选的扩展名提示，缺省时根据数据内容识别格式
+pub fn read_metadata_from_bytes(
+    data: &[u8],
+    hint_ext: Option<&str>,
+    options: &MetadataOptions,
+) -> Result<ScannedSong, String> {
+    let tagged_file = open_tagged_bytes(data, hint_ext)?;
+
+    let is_sq = match hint_ext {
+        Some(ext) => {
+            LOSSLESS_EXTENSIONS.contains(&ext.trim_start_matches('.').to_lowercase().as_str())
+        }
+        None => matches!(
+            tagged_file.file_type(),
+            FileType::Flac | FileType::Wav | FileType::Ape | FileType::Aiff
+        ),
+    };
+
+    let mut song = build_song(
+        &tagged_file,
+        SongSource {
+            path: None,
+            // 没有路径时使用内容哈希作为 ID
+            id: format!("{:x}", md5::compute(data)),
+            file_path: String::new(),
+            file_size: data.len() as u64,
+            file_modified: None,
+            date_added_ms: None,
+            is_sq,
+        },
+        options,
+    );
+
+    if options.audio_hash {
+        let mss = MediaSourceStream::new(Box::new(Cursor::new(data.to_vec())), Default::default());
+        song.audio_hash = hash_audio_stream(mss, hint_ext).ok();
+    }
+
+    Ok(song)
+}
+
+/// 使用 lofty 解析内存中的音频数据
+pub fn open_tagged_bytes(data: &[u8], hint_ext: Option<&str>) -> Result<TaggedFile, String> {
+    let probe = Probe::new(Cursor::new(data));
+    let probe = match hint_ext.and_then(|ext| FileType::from_ext(ext.trim_start_matches('.'))) {
+        Some(file_type) => probe.set_file_type(file_type),
+        None => probe
+            .guess_file_type()
+            .map_err(|e| format!("无法识别音频格式: {}", e))?,
+    };
+
+    probe.read().map_err(|e| format!("无法读取音频数据: {}", e))
+}
+
+/// 构建 ScannedSong 所需的来源信息（本地文件或内存数据）
+struct SongSource<'a> {
+    /// 本地文件路径（内存数据为 None）
+    path: Option<&'a Path>,
+    id: String,
+    file_path: String,
+    file_size: u64,
+    file_modified: Option<i64>,
+    date_added_ms: Option<u64>,
+    is_sq: bool,
+}
+
+/// 从已解析的音频文件构建 ScannedSong（不会再次打开文件读取标签；MP3 会另外扫描
+/// ID3v2 帧头查找 CHAP 章节，开启 `lame_gapless` 时还会读取首帧的 LAME 信息头）
+pub fn song_from_tagged(
+    path: &Path,
+    tagged_file: &TaggedFile,
+    options: &MetadataOptions,
+) -> Result<ScannedSong, String> {
     let file_path_str = path.to_string_lossy().to_string();
 
-    // 获取文件大小
-    let file_size = std::fs::metadata(path)
-        .map_err(|e| format!("无法获取文件信息: {}", e))?
-        .len();
+    // 获取文件大小和修改时间
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| format!("无法获取文件信息: {}", e))?;
+    let file_size = metadata.len();
+    let file_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
 
-    // 使用 lofty 读取音频文件
-    let tagged_file = Probe::open(path)
-        .map_err(|e| format!("无法打开文件: {}", e))?
-        .read()
-        .map_err(|e| format!("无法读取音频文件: {}", e))?;
+    let mut song = build_song(
+        tagged_file,
+        SongSource {
+            path: Some(path),
+            id: song_id(path),
+            file_path: file_path_str,
+            file_size,
+            file_modified,
+            date_added_ms: date_added_ms(&metadata),
+            is_sq: is_lossless_format(path),
+        },
+        options,
+    );
+
+    // MP3 播客的章节在 ID3v2 CHAP 帧中
+    if song.chapters.is_empty() && tagged_file.file_type() == FileType::Mpeg {
+        song.chapters = chapters::read_id3_chapters(path);
+    }
+
+    // 没有 iTunSMPB 的 MP3 再读取 LAME 信息头（可选）
+    if options.lame_gapless
+        && song.encoder_delay.is_none()
+        && tagged_file.file_type() == FileType::Mpeg
+    {
+        if let Some((delay, padding)) = gapless::read_lame_gapless(path) {
+            song.encoder_delay = Some(delay);
+            song.encoder_padding = Some(padding);
+        }
+    }
+
+    // 音频内容哈希（可选，失败时不影响其他元数据）
+    if options.audio_hash {
+        song.audio_hash = compute_audio_hash(path).ok();
+    }
+
+    // 峰值分析（可选，同样不影响其他元数据）
+    if options.analyze_audio {
+        if let Ok(info) = analysis::analyze_peak(path) {
+            song.peak = Some(info.peak);
+            song.clip_ratio = Some(info.clip_ratio);
+        }
+    }
+
+    Ok(song)
+}
 
+/// 根据标签和音频属性构建 ScannedSong
+fn build_song(
+    tagged_file: &TaggedFile,
+    source: SongSource,
+    options: &MetadataOptions,
+) -> ScannedSong {
     // 获取音频属性
     let properties = tagged_file.properties();
     let duration = properties.duration().as_secs_f64();
-    let sample_rate = properties.sample_rate().unwrap_or(0);
+    let sample_rate = properties.sample_rate();
     let bit_depth = properties.bit_depth();
+    let bitrate = properties.audio_bitrate().or_else(|| properties.overall_bitrate());
+    let channels = properties.channels();
 
     // 判断音质
-    let is_sq = is_lossless_format(path);
-    let is_hr = sample_rate > 44100 || bit_depth.map(|d| d > 16).unwrap_or(false);
+    let is_hr = sample_rate.unwrap_or(0) > 44100 || bit_depth.map(|d| d > 16).unwrap_or(false);
 
     // 获取标签信息
-    let tag = tagged_file.primary_tag().or_else(|| tagged_file.first_tag());
+    let tag = primary_or_first_tag(tagged_file);
 
     let title = tag
         .and_then(|t| t.title().map(|s| s.to_string()))
         .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| extract_filename(path));
+        .unwrap_or_else(|| match source.path {
+            Some(path) => extract_filename(path),
+            None => "未知标题".to_string(),
+        });
 
-    let artist = tag
-        .and_then(|t| t.artist().map(|s| s.to_string()))
-        .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| "未知艺术家".to_string());
+    // 多值标签
+    let separators = &options.separators;
+    let artists = read_multi_value(tag, &ItemKey::TrackArtist, separators);
+    let album_artists = read_multi_value(tag, &ItemKey::AlbumArtist, separators);
+    let genres = read_multi_value(tag, &ItemKey::Genre, separators);
+    let composers = read_multi_value(tag, &ItemKey::Composer, separators);
+
+    // 兼容旧字段：单个艺术家时保持标签原值，多个时拼接
+    let artist = if artists.len() > 1 {
+        artists.join(JOIN_SEPARATOR)
+    } else {
+        tag.and_then(|t| t.artist().map(|s| s.to_string()))
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "未知艺术家".to_string())
+    };
 
     let album = tag
         .and_then(|t| t.album().map(|s| s.to_string()))
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| "未知专辑".to_string());
 
-    // 提取封面
-    let cover_url = tag.and_then(|t| {
-        t.pictures().first().map(|pic| {
-            let mime = pic.mime_type().map(|m| m.as_str()).unwrap_or("image/jpeg");
-            let b64 = BASE64.encode(pic.data());
-            format!("data:{};base64,{}", mime, b64)
-        })
-    });
+    // 排序名（缺失时由前端自行处理，如去掉前置冠词）
+    let artist_sort = read_text(tag, &ItemKey::TrackArtistSortOrder);
+    let album_artist_sort = read_text(tag, &ItemKey::AlbumArtistSortOrder);
+    let album_sort = read_text(tag, &ItemKey::AlbumTitleSortOrder);
 
-    // 使用文件路径的哈希作为唯一 ID（确保同一文件每次扫描 ID 相同）
-    let id = format!("{:x}", md5::compute(&file_path_str));
+    let (date, year) = read_date(tag);
 
-    Ok(ScannedSong {
-        id,
+    // 无缝播放信息：只有 MP3 / AAC 等有损格式才有编码器延迟，FLAC / Opus 等无需处理
+    let has_encoder_delay = matches!(
+        tagged_file.file_type(),
+        FileType::Mpeg | FileType::Mp4 | FileType::Aac
+    );
+    let gapless_info = tag.filter(|_| has_encoder_delay).and_then(gapless::read_itunsmpb);
+
+    // Vorbis 注释中的章节（ID3v2 CHAP 帧需要读取文件，见 song_from_tagged）
+    let chapters = tag.map(chapters::read_vorbis_chapters).unwrap_or_default();
+    let musicbrainz_album_id = read_text(tag, &ItemKey::MusicBrainzReleaseId);
+    let bpm = read_bpm(tag);
+    let compilation = read_flag(tag, &ItemKey::FlagCompilation);
+    let key = read_text(tag, &ItemKey::InitialKey).and_then(|k| musical_key::normalize_key(&k));
+
+    // 音轨号与碟号；没有碟号时按第 1 碟处理，便于与多碟专辑一起排序
+    let (track_no, track_total) = read_number_pair(tag, ItemKey::TrackNumber, ItemKey::TrackTotal);
+    let (disc_no, disc_total) = read_number_pair(tag, ItemKey::DiscNumber, ItemKey::DiscTotal);
+    let disc_no = Some(disc_no.unwrap_or(1));
+
+    // 提取封面：开启封面缓存时写入缓存并返回缓存地址，否则内联为 base64
+    let pictures = tag.map(|t| t.pictures()).unwrap_or_default();
+    let (cover_url, cover_hash) = match &options.cover_cache {
+        Some(cache) => {
+            let hash = match source.path {
+                Some(path) => cover::cache_embedded_or_folder_cover(path, pictures, cache),
+                None => cover::cache_front_cover(pictures, cache),
+            }
+            .ok()
+            .flatten();
+            let url = hash
+                .as_deref()
+                .and_then(|h| cache.get_cover_url(h, CoverSize::Mid));
+            (url, hash)
+        }
+        None => {
+            let url = pictures.first().map(|pic| {
+                let mime = pic.mime_type().map(|m| m.as_str()).unwrap_or("image/jpeg");
+                let b64 = BASE64.encode(pic.data());
+                format!("data:{};base64,{}", mime, b64)
+            });
+            (url, None)
+        }
+    };
+
+    // 内嵌歌词
+    let lyrics = tag.and_then(embedded_lyrics);
+    let lyrics_synced = lyrics.as_deref().map(lyrics::is_synced);
+
+    // 响度增益
+    let track_gain_db =
+        tag.and_then(|t| read_gain(t, ItemKey::ReplayGainTrackGain, "R128_TRACK_GAIN"));
+    let album_gain_db =
+        tag.and_then(|t| read_gain(t, ItemKey::ReplayGainAlbumGain, "R128_ALBUM_GAIN"));
+
+    let mut song = ScannedSong {
+        id: source.id,
         title,
         artist,
         album,
         duration,
-        file_path: file_path_str,
-        file_size,
+        file_path: source.file_path,
+        // file_path 是有损转换结果时，额外提供可还原的 file:// URL
+        file_url: source.path.and_then(file_url),
+        file_size: source.file_size,
         cover_url,
         is_hr: Some(is_hr),
-        is_sq: Some(is_sq),
-    })
+        is_sq: Some(source.is_sq),
+        file_modified: source.file_modified,
+        date_added_ms: source.date_added_ms,
+        lyrics,
+        lyrics_synced,
+        track_gain_db,
+        album_gain_db,
+        bitrate,
+        sample_rate,
+        bit_depth,
+        channels,
+        artists,
+        album_artists,
+        genres,
+        composers,
+        track_no,
+        track_total,
+        disc_no,
+        disc_total,
+        audio_hash: None,
+        cover_hash,
+        artist_sort,
+        album_artist_sort,
+        album_sort,
+        date,
+        year,
+        musicbrainz_album_id,
+        compilation,
+        bpm,
+        key,
+        encoder_delay: gapless_info.map(|(delay, _)| delay),
+        encoder_padding: gapless_info.map(|(_, padding)| padding),
+        chapters,
+        ..Default::default()
+    };
+
+    if let Some(encoding) = options.legacy_encoding {
+        if mojibake::repair_song(&mut song, encoding) {
+            song.repaired_encoding = Some(encoding.name().to_string());
+        }
+    }
+    song
 }
 
 /// Read audio file metadata with modification time (for incremental scanning)
 pub fn read_metadata_with_mtime(path: &Path) -> Result<ScannedSongWithMtime, String> {
+    if is_dsd_file(path) {
+        let song = read_dsd_metadata(path, &MetadataOptions::default())?;
+        return Ok(ScannedSongWithMtime {
+            id: song.id,
+            title: song.title,
+            artist: song.artist,
+            album: song.album,
+            duration: song.duration,
+            file_path: song.file_path,
+            file_size: song.file_size,
+            is_hr: song.is_hr,
+            is_sq: song.is_sq,
+            file_modified: song.file_modified.unwrap_or(0),
+            album_artist: Some(song.album_artists.join(" / ")).filter(|a| !a.is_empty()),
+            musicbrainz_album_id: song.musicbrainz_album_id,
+            compilation: song.compilation,
+        });
+    }
+
     let file_path_str = path.to_string_lossy().to_string();
 
     // Get file metadata
@@ -199,8 +1002,15 @@ pub fn read_metadata_with_mtime(path: &Path) -> Result<ScannedSongWithMtime, Str
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| "未知专辑".to_string());
 
+    // Album grouping keys (same separators as a default scan)
+    let album_artists =
+        read_multi_value(tag, &ItemKey::AlbumArtist, &MetadataOptions::default().separators);
+    let album_artist = Some(album_artists.join(" / ")).filter(|a| !a.is_empty());
+    let musicbrainz_album_id = read_text(tag, &ItemKey::MusicBrainzReleaseId);
+    let compilation = read_flag(tag, &ItemKey::FlagCompilation);
+
     // Use file path hash as unique ID
-    let id = format!("{:x}", md5::compute(&file_path_str));
+    let id = song_id(path);
 
     Ok(ScannedSongWithMtime {
         id,
@@ -213,9 +1023,23 @@ pub fn read_metadata_with_mtime(path: &Path) -> Result<ScannedSongWithMtime, Str
         is_hr: Some(is_hr),
         is_sq: Some(is_sq),
         file_modified,
+        album_artist,
+        musicbrainz_album_id,
+        compilation,
     })
 }
 
+/// 文件加入曲库的时间（Unix 毫秒）：优先取创建时间（birth time），
+/// 文件系统不支持时（如部分 Linux 文件系统）退回修改时间
+pub fn date_added_ms(metadata: &std::fs::Metadata) -> Option<u64> {
+    metadata
+        .created()
+        .or_else(|_| metadata.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
+
 /// Get file modification time without reading full metadata
 #[allow(dead_code)]
 pub fn get_file_mtime(path: &Path) -> Result<i64, String> {
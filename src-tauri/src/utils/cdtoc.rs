@@ -0,0 +1,212 @@
+//! 音频 CD 目录（TOC）读取与 MusicBrainz 光盘 ID 计算
+//!
+//! - Linux：对光驱设备（如 /dev/sr0）调用 CDROMREADTOCHDR / CDROMREADTOCENTRY ioctl
+//! - Windows：系统把音轨显示为 Track01.cda 等文件，文件内记录了起始扇区和长度，
+//!   传入盘符（如 "D:"）即可读取，无需直接访问设备
+
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sha1::{Digest, Sha1};
+
+use crate::models::{CdToc, CdTrack};
+
+/// 光盘开头的 2 秒引导区，MusicBrainz 的偏移量包含这部分
+const PREGAP_FRAMES: u32 = 150;
+
+/// 由音轨列表与导出区（lead-out）位置构建 TOC，计算光盘 ID
+fn build_toc(mut tracks: Vec<CdTrack>, lead_out: u32) -> Result<CdToc, String> {
+    tracks.sort_by_key(|t| t.number);
+    if tracks.is_empty() {
+        return Err("光盘上没有音轨".to_string());
+    }
+    let disc_id = musicbrainz_disc_id(&tracks, lead_out);
+    Ok(CdToc {
+        tracks,
+        lead_out_frame: lead_out,
+        musicbrainz_disc_id: disc_id,
+    })
+}
+
+/// 计算 MusicBrainz 光盘 ID：对首尾音轨号、导出区偏移和 99 个音轨偏移的十六进制
+/// 文本做 SHA-1，再以 MusicBrainz 的 base64 变体（"+/=" 换成 "._-"）编码
+pub fn musicbrainz_disc_id(tracks: &[CdTrack], lead_out: u32) -> String {
+    let first = tracks.first().map(|t| t.number).unwrap_or(1);
+    let last = tracks.last().map(|t| t.number).unwrap_or(1);
+
+    let mut offsets = [0u32; 100];
+    offsets[0] = lead_out + PREGAP_FRAMES;
+    for track in tracks {
+        if let Some(slot) = offsets.get_mut(track.number as usize) {
+            *slot = track.start_frame + PREGAP_FRAMES;
+        }
+    }
+
+    let mut text = format!("{:02X}{:02X}", first, last);
+    for offset in offsets {
+        text.push_str(&format!("{:08X}", offset));
+    }
+
+    BASE64
+        .encode(Sha1::digest(text.as_bytes()))
+        .replace('+', ".")
+        .replace('/', "_")
+        .replace('=', "-")
+}
+
+/// 解析 Windows 的 .cda 文件（44 字节的 RIFF "CDDA"），返回音轨信息
+fn parse_cda(data: &[u8]) -> Option<CdTrack> {
+    if data.len() < 44 || &data[0..4] != b"RIFF" || &data[8..12] != b"CDDA" {
+        return None;
+    }
+    let u16_at = |pos: usize| u16::from_le_bytes([data[pos], data[pos + 1]]);
+    let u32_at = |pos: usize| {
+        u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]])
+    };
+    Some(CdTrack {
+        number: u16_at(22) as u8,
+        start_frame: u32_at(28),
+        length_frames: u32_at(32),
+    })
+}
+
+/// 从盘符下的 .cda 文件读取 TOC
+/// 导出区按最后一条音轨的结尾计算（增强型 CD 的数据轨不会出现在 .cda 中）
+fn read_toc_from_cda(drive: &Path) -> Result<CdToc, String> {
+    let entries = std::fs::read_dir(drive).map_err(|e| format!("无法读取光驱: {}", e))?;
+    let tracks: Vec<CdTrack> = entries
+        .flatten()
+        .filter(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("cda"))
+        })
+        .filter_map(|e| parse_cda(&std::fs::read(e.path()).ok()?))
+        .collect();
+
+    let lead_out = tracks
+        .iter()
+        .map(|t| t.start_frame + t.length_frames)
+        .max()
+        .unwrap_or(0);
+    build_toc(tracks, lead_out)
+}
+
+/// 通过 ioctl 从光驱设备读取 TOC
+#[cfg(target_os = "linux")]
+fn read_toc_from_device(device: &Path) -> Result<CdToc, String> {
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::io::AsRawFd;
+
+    const CDROMREADTOCHDR: libc::c_ulong = 0x5305;
+    const CDROMREADTOCENTRY: libc::c_ulong = 0x5306;
+    const CDROM_LBA: u8 = 0x01;
+    const CDROM_LEADOUT: u8 = 0xAA;
+    /// 控制字段中的数据轨标志
+    const CDROM_DATA_TRACK: u8 = 0x04;
+    /// 增强型 CD 音频会话与数据会话之间的间隔帧数
+    const DATA_SESSION_GAP: u32 = 11400;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct TocHeader {
+        first_track: u8,
+        last_track: u8,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct TocEntry {
+        track: u8,
+        /// 低 4 位为 adr，高 4 位为 ctrl
+        adr_ctrl: u8,
+        format: u8,
+        lba: i32,
+        /// 数据轨的模式，只为与内核结构布局一致，不读取
+        _datamode: u8,
+    }
+
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(device)
+        .map_err(|e| format!("无法打开光驱: {}", e))?;
+    let fd = file.as_raw_fd();
+
+    let mut header = TocHeader::default();
+    // SAFETY: fd 在 file 存活期间有效，header 与内核的 cdrom_tochdr 布局一致
+    if unsafe { libc::ioctl(fd, CDROMREADTOCHDR as _, &mut header) } != 0 {
+        return Err(format!("无法读取光盘目录: {}", std::io::Error::last_os_error()));
+    }
+
+    let read_entry = |track: u8| -> Result<TocEntry, String> {
+        let mut entry = TocEntry {
+            track,
+            format: CDROM_LBA,
+            ..Default::default()
+        };
+        // SAFETY: 同上，entry 与内核的 cdrom_tocentry 布局一致
+        if unsafe { libc::ioctl(fd, CDROMREADTOCENTRY as _, &mut entry) } != 0 {
+            return Err(format!("无法读取音轨 {}: {}", track, std::io::Error::last_os_error()));
+        }
+        // lba 与 MSF 地址共用同一字段，只有内核按 LBA 返回时才能直接使用
+        if entry.format != CDROM_LBA {
+            return Err(format!("无法读取音轨 {}: 光驱未返回 LBA 地址", track));
+        }
+        Ok(entry)
+    };
+
+    let lead_out = read_entry(CDROM_LEADOUT)?.lba.max(0) as u32;
+    let mut audio: Vec<(u8, u32)> = Vec::new();
+    let mut audio_lead_out = lead_out;
+    for track in header.first_track..=header.last_track {
+        let entry = read_entry(track)?;
+        let start = entry.lba.max(0) as u32;
+        if (entry.adr_ctrl >> 4) & CDROM_DATA_TRACK != 0 {
+            // 增强型 CD：数据轨前还有 11400 帧的会话间隔，音频部分到此结束
+            audio_lead_out = audio_lead_out.min(start.saturating_sub(DATA_SESSION_GAP));
+        } else {
+            audio.push((entry.track, start));
+        }
+    }
+
+    // 音轨长度 = 下一条音轨（或音频部分结尾）的起始位置 - 本音轨起始位置
+    let tracks = audio
+        .iter()
+        .enumerate()
+        .map(|(i, (number, start))| {
+            let end = audio.get(i + 1).map(|(_, s)| *s).unwrap_or(audio_lead_out);
+            CdTrack {
+                number: *number,
+                start_frame: *start,
+                length_frames: end.saturating_sub(*start),
+            }
+        })
+        .collect();
+    build_toc(tracks, audio_lead_out)
+}
+
+/// 读取光盘 TOC：传入目录（Windows 盘符 "D:" 等）时读取 .cda 文件，
+/// 否则按光驱设备（Linux 的 /dev/sr0 等）处理
+pub fn read_toc(drive: &str) -> Result<CdToc, String> {
+    // "D:" 本身表示当前目录，需补上根目录
+    let drive = if drive.len() == 2 && drive.ends_with(':') {
+        format!("{}\\", drive)
+    } else {
+        drive.to_string()
+    };
+    let path = Path::new(&drive);
+    if path.is_dir() {
+        return read_toc_from_cda(path);
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        read_toc_from_device(path)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(format!("不支持的光驱路径: {}", drive))
+    }
+}
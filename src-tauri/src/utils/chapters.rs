@@ -0,0 +1,193 @@
+//! 章节信息：Vorbis 注释中的章节（Ogg / Opus 有声书）和 ID3v2 CHAP 帧（MP3 播客）
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use lofty::tag::{ItemKey, Tag};
+
+use crate::models::Chapter;
+
+/// 单个 CHAP 帧（含 TIT2 等子帧）的最大长度，更大的视为损坏
+const MAX_CHAP_FRAME: usize = 64 * 1024;
+
+/// 解析 Vorbis 章节时间 "HH:MM:SS.mmm"（小时、分钟可省略），返回毫秒
+fn parse_chapter_time(value: &str) -> Option<u64> {
+    let mut seconds = 0.0f64;
+    for part in value.trim().split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    (seconds >= 0.0).then(|| (seconds * 1000.0).round() as u64)
+}
+
+/// 从 Vorbis 注释读取章节：CHAPTER000=00:00:00.000，CHAPTER000NAME=标题
+pub fn read_vorbis_chapters(tag: &Tag) -> Vec<Chapter> {
+    let mut chapters: BTreeMap<u32, (Option<u64>, Option<String>)> = BTreeMap::new();
+
+    for item in tag.items() {
+        let ItemKey::Unknown(key) = item.key() else {
+            continue;
+        };
+        let key = key.to_uppercase();
+        let Some(rest) = key.strip_prefix("CHAPTER") else {
+            continue;
+        };
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let Ok(index) = digits.parse::<u32>() else {
+            continue;
+        };
+        let Some(value) = item.value().text() else {
+            continue;
+        };
+
+        let chapter = chapters.entry(index).or_default();
+        match &rest[digits.len()..] {
+            "" => chapter.0 = parse_chapter_time(value),
+            "NAME" => chapter.1 = Some(value.trim().to_string()).filter(|v| !v.is_empty()),
+            _ => {}
+        }
+    }
+
+    chapters
+        .into_values()
+        .filter_map(|(start_ms, title)| Some(Chapter { start_ms: start_ms?, title }))
+        .collect()
+}
+
+/// 解码 ID3v2 文本（首字节为编码：0 Latin-1，1 带 BOM 的 UTF-16，2 UTF-16BE，3 UTF-8）
+fn decode_id3_text(data: &[u8]) -> Option<String> {
+    let (&encoding, text) = data.split_first()?;
+    let decoded = match encoding {
+        0 => text.iter().map(|&b| b as char).collect(),
+        1 | 2 => {
+            let (big_endian, text) = match text {
+                [0xFF, 0xFE, rest @ ..] => (false, rest),
+                [0xFE, 0xFF, rest @ ..] => (true, rest),
+                _ => (encoding == 2, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|c| {
+                    if big_endian {
+                        u16::from_be_bytes([c[0], c[1]])
+                    } else {
+                        u16::from_le_bytes([c[0], c[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(text).to_string(),
+    };
+    Some(decoded.trim_end_matches('\0').trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// 同步安全整数：每字节 7 位
+fn synchsafe(bytes: [u8; 4]) -> usize {
+    bytes.iter().fold(0usize, |acc, b| (acc << 7) | (*b & 0x7F) as usize)
+}
+
+/// ID3v2 帧头中的帧大小（ID3v2.4 为同步安全整数）
+fn frame_size(bytes: [u8; 4], version: u8) -> usize {
+    if version >= 4 {
+        synchsafe(bytes)
+    } else {
+        u32::from_be_bytes(bytes) as usize
+    }
+}
+
+/// 遍历 ID3v2 帧，返回 (帧 ID, 帧内容)
+fn id3_frames(data: &[u8], version: u8) -> Vec<(&[u8], &[u8])> {
+    let mut frames = Vec::new();
+    let mut pos = 0;
+    while pos + 10 <= data.len() {
+        let id = &data[pos..pos + 4];
+        // 遇到填充区（全零）即结束
+        if id[0] == 0 {
+            break;
+        }
+        let size_bytes = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+        let size = frame_size(size_bytes, version);
+        let Some(body) = data.get(pos + 10..pos + 10 + size) else {
+            break;
+        };
+        frames.push((id, body));
+        pos += 10 + size;
+    }
+    frames
+}
+
+/// 解析 CHAP 帧：元素 ID（以 0 结尾）、开始/结束时间（毫秒）、开始/结束偏移，之后为子帧
+fn parse_chap_frame(body: &[u8], version: u8) -> Option<Chapter> {
+    let id_end = body.iter().position(|&b| b == 0)?;
+    let times = body.get(id_end + 1..id_end + 17)?;
+    let start_ms = u32::from_be_bytes([times[0], times[1], times[2], times[3]]) as u64;
+
+    let title = id3_frames(&body[id_end + 17..], version)
+        .into_iter()
+        .find(|(id, _)| *id == b"TIT2")
+        .and_then(|(_, text)| decode_id3_text(text));
+
+    Some(Chapter { start_ms, title })
+}
+
+/// 从 MP3 文件开头的 ID3v2 标签读取 CHAP 章节（按开始时间排序）
+/// 只读取帧头，跳过其他帧（如数 MB 的 APIC 封面）的内容
+pub fn read_id3_chapters(path: &Path) -> Vec<Chapter> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut reader = BufReader::new(file);
+    let mut header = [0u8; 10];
+    // CHAP 帧始于 ID3v2.3
+    if reader.read_exact(&mut header).is_err() || &header[..3] != b"ID3" || header[3] < 3 {
+        return Vec::new();
+    }
+    let version = header[3];
+    let flags = header[5];
+    let tag_size = synchsafe([header[6], header[7], header[8], header[9]]);
+
+    // 跳过扩展头（v2.3 的大小不含自身 4 字节，v2.4 为同步安全整数且包含自身）
+    let mut pos = 0;
+    if flags & 0x40 != 0 {
+        let mut ext = [0u8; 4];
+        if reader.read_exact(&mut ext).is_err() {
+            return Vec::new();
+        }
+        let ext_len = if version >= 4 {
+            synchsafe(ext).max(4)
+        } else {
+            u32::from_be_bytes(ext) as usize + 4
+        };
+        if reader.seek_relative(ext_len as i64 - 4).is_err() {
+            return Vec::new();
+        }
+        pos = ext_len;
+    }
+
+    let mut chapters = Vec::new();
+    let mut frame = [0u8; 10];
+    while pos + 10 <= tag_size && reader.read_exact(&mut frame).is_ok() {
+        // 遇到填充区（全零）即结束
+        if frame[0] == 0 {
+            break;
+        }
+        let size = frame_size([frame[4], frame[5], frame[6], frame[7]], version);
+        pos += 10 + size;
+        if pos > tag_size {
+            break;
+        }
+        if &frame[..4] == b"CHAP" && size <= MAX_CHAP_FRAME {
+            let mut body = vec![0; size];
+            if reader.read_exact(&mut body).is_err() {
+                break;
+            }
+            chapters.extend(parse_chap_frame(&body, version));
+        } else if reader.seek_relative(size as i64).is_err() {
+            break;
+        }
+    }
+    chapters.sort_by_key(|c| c.start_ms);
+    chapters
+}
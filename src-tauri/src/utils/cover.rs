@@ -1,38 +1,478 @@
 //! Cover image caching utilities
 //!
 //! Provides three-tier cover caching:
-//! - small: 120x120 thumbnails for list views
-//! - mid: 300x300 covers for album grids
+//! - small: 120x120 thumbnails for list views (configurable)
+//! - mid: 300x300 covers for album grids (configurable)
 //! - orig: Original resolution covers for full-screen view
 
-use image::DynamicImage;
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, ImageDecoder};
+use lofty::picture::{Picture, PictureType};
+use percent_encoding::{percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use rayon::prelude::*;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::Cursor;
+use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use crate::utils::audio::path_bytes;
+use crate::utils::{blurhash, palette};
+
+/// Number of lock shards guarding per-hash cover writes
+const LOCK_SHARDS: usize = 64;
+
+/// Default edge length of the small variant
+pub const DEFAULT_SMALL_SIZE: u32 = 120;
+/// Default edge length of the mid variant
+pub const DEFAULT_MID_SIZE: u32 = 300;
+/// Default encoding quality of the small variant
+pub const DEFAULT_SMALL_QUALITY: u8 = 80;
+/// Default encoding quality of the mid variant
+pub const DEFAULT_MID_QUALITY: u8 = 85;
+
+/// Default file names (without extension) of album art stored next to audio files
+const DEFAULT_FOLDER_ART_NAMES: &[&str] = &["cover", "folder", "front", "albumart"];
+/// Extensions recognized for folder album art
+const FOLDER_ART_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+
+/// BlurHash components (x, y) used for cover placeholders
+const BLURHASH_COMPONENTS: (u32, u32) = (4, 3);
+/// Edge length the mid variant is shrunk to before computing its BlurHash
+const BLURHASH_INPUT_SIZE: u32 = 32;
+
+/// JPEG/lossy WebP quality used when re-encoding originals to strip their metadata
+const STRIPPED_QUALITY: u8 = 95;
+
+/// Characters escaped in asset URL path segments: everything but RFC 3986
+/// unreserved characters, so `#`, `?`, `%`, spaces and `:` are all encoded
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Temp files older than this are leftovers of an interrupted write
+const STALE_TMP_AGE: Duration = Duration::from_secs(60);
+
+/// Error message prefix of covers rejected by `with_min_original_dimension`
+const TOO_SMALL_ERROR: &str = "Cover below minimum dimension";
+
+/// File in the cache root mapping album IDs to user-chosen cover hashes
+const ALBUM_COVERS_FILE: &str = "album_covers.json";
+
+/// Cover downloads give up if the server doesn't accept the connection in time
+const DOWNLOAD_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Cover downloads give up if the whole request takes longer than this
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum number of redirects followed by cover downloads
+const DOWNLOAD_MAX_REDIRECTS: usize = 5;
+
+/// Largest cover accepted from a download; bigger responses are aborted
+const MAX_DOWNLOAD_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Number of sprite sheets kept on disk besides the ones just requested;
+/// older ones are removed
+const MAX_SPRITE_SHEETS: usize = 8;
+
+/// Maximum Hamming distance between dHashes of covers treated as duplicates
+const DHASH_MAX_DISTANCE: u32 = 3;
+
+/// Edge length of the thumbnails compared pixel by pixel before aliasing
+const DEDUPE_SAMPLE_SIZE: u32 = 32;
+
+/// Maximum mean per-channel difference (0-255) between the thumbnails of
+/// covers treated as duplicates
+const DEDUPE_MAX_PIXEL_DIFF: f64 = 4.0;
+
+/// Maximum relative difference between the aspect ratios of duplicates
+const DEDUPE_MAX_ASPECT_DIFF: f64 = 0.01;
+
+/// Number of colors kept in a cover's palette
+const PALETTE_SIZE: usize = 5;
+
+/// Number of newly cached covers between opportunistic size-limit checks
+const ENFORCE_INTERVAL: usize = 100;
 
 /// Cover size variants
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CoverSize {
-    /// 120x120 thumbnail for list views
+    /// Thumbnail for list views (120x120 by default)
     Small,
-    /// 300x300 for album/artist grids
+    /// Album/artist grids (300x300 by default)
     Mid,
     /// Original resolution
     Original,
 }
 
+/// Output encoding for the resized (small/mid) cover variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverFormat {
+    /// JPEG with the given quality (1-100)
+    Jpeg { quality: u8 },
+    /// Lossy WebP with the given quality (1-100)
+    WebP { quality: u8 },
+}
+
+impl CoverFormat {
+    /// File extension used for variants in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CoverFormat::Jpeg { .. } => "jpg",
+            CoverFormat::WebP { .. } => "webp",
+        }
+    }
+
+    /// Largest image edge, in pixels, the format can encode
+    pub fn max_dimension(&self) -> u32 {
+        match self {
+            CoverFormat::Jpeg { .. } => 65535,
+            CoverFormat::WebP { .. } => 16383,
+        }
+    }
+
+    /// Encoding quality (1-100)
+    pub fn quality(&self) -> u8 {
+        match *self {
+            CoverFormat::Jpeg { quality } | CoverFormat::WebP { quality } => quality,
+        }
+    }
+
+    /// The same format with a different quality (clamped to 1-100)
+    pub fn with_quality(self, quality: u8) -> Self {
+        let quality = quality.clamp(1, 100);
+        match self {
+            CoverFormat::Jpeg { .. } => CoverFormat::Jpeg { quality },
+            CoverFormat::WebP { .. } => CoverFormat::WebP { quality },
+        }
+    }
+}
+
+/// How non-square art is fitted into the square small/mid variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverFit {
+    /// Scale and crop to fill the square
+    Fill,
+    /// Scale to fit within the square without cropping. When `pad` is set,
+    /// the result is padded to a full square with that RGBA color (JPEG
+    /// variants flatten transparency, so use WebP for transparent padding)
+    Contain { pad: Option<[u8; 4]> },
+}
+
+/// Details of a cover saved to the cache
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedCover {
+    pub hash: String,
+    /// Width of the original image in pixels
+    pub width: u32,
+    /// Height of the original image in pixels
+    pub height: u32,
+    /// Format (extension) of the stored original, e.g. "png"
+    pub orig_format: String,
+    /// Size of the original image data in bytes
+    pub orig_bytes: u64,
+    /// BlurHash of the cover for placeholder rendering
+    pub blurhash: Option<String>,
+}
+
+/// Position of a cover's small variant within a sprite sheet, in pixels
+#[derive(Debug, Clone, Serialize)]
+pub struct SpriteRect {
+    pub hash: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Which variants of a cover are present in the cache
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CoverVariants {
+    pub small: bool,
+    pub mid: bool,
+    pub orig: bool,
+}
+
+/// (cover hash, dHash) of every canonical original
+type DhashIndex = Vec<(String, u64)>;
+
 /// Cover cache manager
 #[derive(Clone)]
 pub struct CoverCache {
     cache_dir: PathBuf,
+    /// Extra roots searched after `cache_dir`, in order (e.g. a large archive
+    /// disk holding older covers). New covers are only written to `cache_dir`
+    fallback_dirs: Vec<PathBuf>,
+    mid_format: CoverFormat,
+    small_format: CoverFormat,
+    small_size: u32,
+    mid_size: u32,
+    fit: CoverFit,
+    /// Encode JPEG mid variants (and re-encoded originals) as progressive JPEGs
+    progressive: bool,
+    /// Re-encode originals instead of storing the embedded bytes verbatim,
+    /// dropping EXIF/XMP and other metadata
+    strip_metadata: bool,
+    /// Maximum total size of the cache in bytes (None = unbounded)
+    max_bytes: Option<u64>,
+    /// Folder art file names to look for when a file has no embedded cover,
+    /// in order of preference (matched case-insensitively)
+    folder_art_names: Vec<String>,
+    /// Sharded locks so the same hash is never written by two threads at once
+    /// (shared between clones)
+    locks: Arc<Vec<Mutex<()>>>,
+    /// Serializes read-modify-write updates of the album cover overrides file
+    /// (shared between clones)
+    album_covers_lock: Arc<Mutex<()>>,
+    /// Hashes written by this cache in the current run, never evicted
+    /// (shared between clones)
+    written: Arc<Mutex<HashSet<String>>>,
+    /// Covers written since the size limit was last enforced
+    writes_since_enforce: Arc<AtomicUsize>,
+    /// Dedicated pool for decoding/encoding covers (None = caller's thread),
+    /// so caching can't saturate every core while a scan runs
+    pool: Option<Arc<rayon::ThreadPool>>,
+    /// Alias covers that look identical to an already cached one (e.g. the
+    /// same art re-encoded as webp) instead of storing them again
+    perceptual_dedupe: bool,
+    /// Reject covers whose shorter edge is below this many pixels
+    min_original_dimension: Option<u32>,
+    /// Sizes written by `save_cover`; the others are never produced
+    generated_sizes: Vec<CoverSize>,
+    /// dHashes of canonical originals, loaded from their sidecars on first use
+    /// (shared between clones)
+    dhash_index: Arc<Mutex<Option<DhashIndex>>>,
 }
 
 impl CoverCache {
     /// Create a new cover cache manager
     pub fn new(cache_dir: PathBuf) -> Self {
-        Self { cache_dir }
+        Self {
+            cache_dir,
+            fallback_dirs: Vec::new(),
+            mid_format: CoverFormat::Jpeg { quality: DEFAULT_MID_QUALITY },
+            small_format: CoverFormat::Jpeg { quality: DEFAULT_SMALL_QUALITY },
+            small_size: DEFAULT_SMALL_SIZE,
+            mid_size: DEFAULT_MID_SIZE,
+            fit: CoverFit::Fill,
+            progressive: false,
+            strip_metadata: false,
+            max_bytes: None,
+            folder_art_names: DEFAULT_FOLDER_ART_NAMES.iter().map(|s| s.to_string()).collect(),
+            locks: Arc::new((0..LOCK_SHARDS).map(|_| Mutex::new(())).collect()),
+            album_covers_lock: Arc::new(Mutex::new(())),
+            written: Arc::new(Mutex::new(HashSet::new())),
+            writes_since_enforce: Arc::new(AtomicUsize::new(0)),
+            pool: None,
+            perceptual_dedupe: false,
+            min_original_dimension: None,
+            generated_sizes: vec![CoverSize::Small, CoverSize::Mid, CoverSize::Original],
+            dhash_index: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The same cache (directories and shared state) with every setting
+    /// reset to its default, ready to be configured again
+    pub fn with_default_settings(&self) -> Self {
+        Self {
+            fallback_dirs: self.fallback_dirs.clone(),
+            locks: self.locks.clone(),
+            album_covers_lock: self.album_covers_lock.clone(),
+            written: self.written.clone(),
+            writes_since_enforce: self.writes_since_enforce.clone(),
+            dhash_index: self.dhash_index.clone(),
+            ..Self::new(self.cache_dir.clone())
+        }
+    }
+
+    /// Resize small and mid variants to the given edge lengths (in pixels)
+    pub fn with_sizes(mut self, small_size: u32, mid_size: u32) -> Self {
+        self.small_size = small_size;
+        self.mid_size = mid_size;
+        self
+    }
+
+    /// Set how non-square art is fitted into small and mid variants
+    pub fn with_fit(mut self, fit: CoverFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Encode small and mid variants with the given format
+    pub fn with_format(mut self, format: CoverFormat) -> Self {
+        self.mid_format = format;
+        self.small_format = format;
+        self
+    }
+
+    /// Set the encoding quality (1-100) of the mid and small variants,
+    /// keeping their format. Variants cached at another quality are not
+    /// reused by `save_cover`; call `regenerate_variants` (or rescan) to
+    /// rebuild existing covers at the new quality
+    pub fn with_quality(mut self, mid_quality: u8, small_quality: u8) -> Self {
+        self.mid_format = self.mid_format.with_quality(mid_quality);
+        self.small_format = self.small_format.with_quality(small_quality);
+        self
+    }
+
+    /// Encode JPEG mid variants progressively, so they render gracefully
+    /// over slow links (small thumbnails stay baseline)
+    pub fn with_progressive(mut self, progressive: bool) -> Self {
+        self.progressive = progressive;
+        self
+    }
+
+    /// Store originals as a clean re-encode (high quality JPEG for JPEG
+    /// sources, PNG otherwise) so EXIF GPS or other personal metadata in
+    /// embedded art is not kept. The cover hash is still computed from the
+    /// embedded bytes, so a stripped original no longer hashes to its own name
+    pub fn with_strip_metadata(mut self, strip_metadata: bool) -> Self {
+        self.strip_metadata = strip_metadata;
+        self
+    }
+
+    /// Decode and encode covers on a dedicated pool of `threads` workers.
+    /// Callers (e.g. scan workers) block until their cover is done, so at most
+    /// `threads` covers are processed at once
+    pub fn with_pool(mut self, threads: usize) -> Result<Self, String> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .thread_name(|i| format!("cover-cache-{}", i))
+            .build()
+            .map_err(|e| format!("Failed to create cover thread pool: {}", e))?;
+        self.pool = Some(Arc::new(pool));
+        Ok(self)
+    }
+
+    /// Detect near-duplicate covers by perceptual hash. A new cover whose
+    /// dHash is within a few bits of a cached original gets a small alias file
+    /// pointing at that cover instead of its own files. The exact content
+    /// hash stays the key callers use; lookups follow the alias
+    pub fn with_perceptual_dedupe(mut self, enabled: bool) -> Self {
+        self.perceptual_dedupe = enabled;
+        self
+    }
+
+    /// Reject covers smaller than `min_dimension` on either edge (e.g. 64x64
+    /// embedded thumbnails) instead of upscaling them into blurry variants.
+    /// Rejections are detectable with `is_too_small_error`
+    pub fn with_min_original_dimension(mut self, min_dimension: u32) -> Self {
+        self.min_original_dimension = Some(min_dimension).filter(|min| *min > 0);
+        self
+    }
+
+    /// Only produce the given sizes when saving covers (e.g. just small
+    /// thumbnails on low-storage devices). An empty list keeps all three
+    pub fn with_generated_sizes(mut self, sizes: &[CoverSize]) -> Self {
+        if !sizes.is_empty() {
+            self.generated_sizes = sizes.to_vec();
+        }
+        self
+    }
+
+    fn generates(&self, size: CoverSize) -> bool {
+        self.generated_sizes.contains(&size)
+    }
+
+    /// The size written last when saving a cover; its presence marks the
+    /// cover as cached
+    fn marker_size(&self) -> CoverSize {
+        [CoverSize::Mid, CoverSize::Small]
+            .into_iter()
+            .find(|size| self.generates(*size))
+            .unwrap_or(CoverSize::Original)
+    }
+
+    /// Run CPU-heavy cover work on the dedicated pool, if configured
+    fn in_pool<R: Send>(&self, work: impl FnOnce() -> R + Send) -> R {
+        match &self.pool {
+            Some(pool) => pool.install(work),
+            None => work(),
+        }
+    }
+
+    /// Cap the total cache size; least recently used covers are evicted
+    /// by `enforce_limit`
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Set the folder art file names (without extension) used as a fallback
+    /// when a file has no embedded cover; an empty list disables the fallback
+    pub fn with_folder_art_names(mut self, names: Vec<String>) -> Self {
+        self.folder_art_names = names;
+        self
+    }
+
+    /// Find album art stored as an image file in a directory
+    /// (e.g. `folder.jpg`, `cover.png`), honoring the configured name order
+    fn find_folder_art(&self, dir: &Path) -> Option<PathBuf> {
+        let images: Vec<(String, PathBuf)> = fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| FOLDER_ART_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            })
+            .filter_map(|path| {
+                let stem = path.file_stem()?.to_str()?.to_lowercase();
+                Some((stem, path))
+            })
+            .collect();
+
+        self.folder_art_names.iter().find_map(|name| {
+            let name = name.to_lowercase();
+            images
+                .iter()
+                .find(|(stem, path)| *stem == name && path.is_file())
+                .map(|(_, path)| path.clone())
+        })
+    }
+
+    /// Get the encoding used for a resized variant (None for originals)
+    fn variant_format(&self, size: CoverSize) -> Option<CoverFormat> {
+        match size {
+            CoverSize::Small => Some(self.small_format),
+            CoverSize::Mid => Some(self.mid_format),
+            CoverSize::Original => None,
+        }
+    }
+
+    /// Also look up covers in `roots` (after the primary cache directory, in
+    /// order), so old covers can be moved to slower storage
+    pub fn with_fallback_roots(mut self, roots: Vec<PathBuf>) -> Self {
+        self.fallback_dirs = roots.into_iter().filter(|r| *r != self.cache_dir).collect();
+        self
+    }
+
+    /// Get the lock shard guarding a hash
+    fn hash_lock(&self, hash: &str) -> &Mutex<()> {
+        let shard = hash
+            .get(..2)
+            .and_then(|p| usize::from_str_radix(p, 16).ok())
+            .unwrap_or(0);
+        &self.locks[shard % LOCK_SHARDS]
+    }
+
+    /// Root directory of the cache
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// All cache roots: the primary directory first, then the fallbacks
+    fn roots(&self) -> impl Iterator<Item = &Path> {
+        std::iter::once(self.cache_dir.as_path())
+            .chain(self.fallback_dirs.iter().map(|dir| dir.as_path()))
     }
 
     /// Get an Arc-wrapped clone for use in parallel processing
@@ -42,24 +482,214 @@ impl CoverCache {
 
     /// Get the cache directory for a given size
     fn size_dir(&self, size: CoverSize) -> PathBuf {
-        match size {
-            CoverSize::Small => self.cache_dir.join("small"),
-            CoverSize::Mid => self.cache_dir.join("mid"),
-            CoverSize::Original => self.cache_dir.join("orig"),
-        }
+        size_dir_in(&self.cache_dir, size)
+    }
+
+    /// Get the cache directory for a given size in every root
+    fn size_dirs(&self, size: CoverSize) -> Vec<PathBuf> {
+        self.roots().map(|root| size_dir_in(root, size)).collect()
     }
 
     /// Get the path for a cached cover by hash
     fn cover_path(&self, hash: &str, size: CoverSize, ext: &str) -> PathBuf {
+        self.cover_file(hash, size, &format!("{}.{}", hash, ext))
+    }
+
+    /// Get the path for a file name inside a hash's prefix directory
+    fn cover_file(&self, hash: &str, size: CoverSize, file_name: &str) -> PathBuf {
+        let prefix = &hash[..2.min(hash.len())];
+        self.size_dir(size).join(prefix).join(file_name)
+    }
+
+    /// Find a file inside a hash's prefix directory, checking every root in order
+    fn find_file(&self, hash: &str, size: CoverSize, file_name: &str) -> Option<PathBuf> {
         let prefix = &hash[..2.min(hash.len())];
-        self.size_dir(size).join(prefix).join(format!("{}.{}", hash, ext))
+        self.roots()
+            .map(|root| size_dir_in(root, size).join(prefix).join(file_name))
+            .find(|path| path.exists())
+    }
+
+    /// Get the path of the alias file of a near-duplicate cover
+    fn alias_path(&self, hash: &str) -> PathBuf {
+        self.cover_file(hash, CoverSize::Original, &format!("{}.alias", hash))
+    }
+
+    /// Get the canonical cover a near-duplicate hash points at, if any
+    fn alias_target(&self, hash: &str) -> Option<String> {
+        let path = self.find_file(hash, CoverSize::Original, &format!("{}.alias", hash))?;
+        fs::read_to_string(path)
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|target| !target.is_empty() && target != hash)
+    }
+
+    /// Get the path of the dHash sidecar of a canonical original
+    fn dhash_path(&self, hash: &str) -> PathBuf {
+        self.cover_file(hash, CoverSize::Original, &format!("{}.dh", hash))
+    }
+
+    /// Find a cached cover that looks identical, recording `dhash` for `hash`
+    /// when there is none. Returns the canonical cover's hash.
+    /// A close dHash only nominates candidates; a candidate must also have
+    /// the same aspect ratio and nearly the same pixels
+    fn find_or_index_duplicate(&self, hash: &str, img: &DynamicImage) -> Option<String> {
+        let dhash = compute_dhash(img);
+
+        // Comparing decodes cached covers from disk, so it runs without the
+        // index lock; the index is append-only, so only entries added in the
+        // meantime need another look before this hash is recorded
+        let mut checked = 0;
+        loop {
+            let (candidates, len) = {
+                let mut index = self.dhash_index.lock().unwrap_or_else(|e| e.into_inner());
+                let index = index.get_or_insert_with(|| self.load_dhash_index());
+                let candidates = dhash_candidates(&index[checked..], hash, dhash);
+                if candidates.is_empty() {
+                    let sidecar = format!("{:016x}", dhash);
+                    let _ = write_atomic(&self.dhash_path(hash), sidecar.as_bytes());
+                    index.push((hash.to_string(), dhash));
+                    return None;
+                }
+                (candidates, index.len())
+            };
+            if let Some(duplicate) = candidates.into_iter().find(|o| self.looks_identical(o, img)) {
+                return Some(duplicate);
+            }
+            checked = len;
+        }
+    }
+
+    /// Compare a decoded cover against a cached one. Uses the cached original
+    /// when present, otherwise the marker variant (with `img` resized the same
+    /// way). Fails when the cached cover has been evicted or can't be decoded
+    fn looks_identical(&self, hash: &str, img: &DynamicImage) -> bool {
+        let (path, candidate) = match self.find_cover_path(hash, CoverSize::Original) {
+            Some(path) => (path, None),
+            None => {
+                let size = self.marker_size();
+                let Some(path) = self.find_cover_path(hash, size) else {
+                    return false;
+                };
+                let dim = match size {
+                    CoverSize::Small => self.small_size,
+                    _ => self.mid_size,
+                };
+                (path, Some(self.resize_variant(img, dim)))
+            }
+        };
+        let candidate = candidate.as_ref().unwrap_or(img);
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+        let Ok(reference) = fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| decode_image(&data, ext))
+        else {
+            return false;
+        };
+
+        let (width, height) = reference.dimensions();
+        let (cand_width, cand_height) = candidate.dimensions();
+        let aspect = width as f64 / height.max(1) as f64;
+        let cand_aspect = cand_width as f64 / cand_height.max(1) as f64;
+        if (aspect - cand_aspect).abs() > aspect * DEDUPE_MAX_ASPECT_DIFF {
+            return false;
+        }
+        mean_pixel_diff(&reference, candidate) <= DEDUPE_MAX_PIXEL_DIFF
+    }
+
+    /// Read every dHash sidecar in the cache
+    fn load_dhash_index(&self) -> DhashIndex {
+        self.size_dirs(CoverSize::Original)
+            .into_iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten())
+            .filter_map(|entry| fs::read_dir(entry.path()).ok())
+            .flat_map(|sub_entries| sub_entries.flatten().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|e| e == "dh"))
+            .filter_map(|path| {
+                let hash = hash_from_path(&path)?.to_string();
+                let dhash = u64::from_str_radix(fs::read_to_string(&path).ok()?.trim(), 16).ok()?;
+                Some((hash, dhash))
+            })
+            .collect()
+    }
+
+    /// Get the file stem for the configured variant of a hash.
+    /// Variants at the default dimension, fit and quality keep the plain
+    /// `{hash}` name so existing caches stay valid; other settings get a
+    /// suffix (`{hash}_600`, `{hash}_300c`, `{hash}_300q95`), so files cached
+    /// with older settings are still served until they are regenerated.
+    fn variant_stem(&self, hash: &str, size: CoverSize) -> String {
+        let (dim, default_dim, quality, default_quality) = match size {
+            CoverSize::Small => (
+                self.small_size,
+                DEFAULT_SMALL_SIZE,
+                self.small_format.quality(),
+                DEFAULT_SMALL_QUALITY,
+            ),
+            CoverSize::Mid => (
+                self.mid_size,
+                DEFAULT_MID_SIZE,
+                self.mid_format.quality(),
+                DEFAULT_MID_QUALITY,
+            ),
+            CoverSize::Original => return hash.to_string(),
+        };
+
+        let fit = match self.fit {
+            CoverFit::Fill => String::new(),
+            CoverFit::Contain { pad: None } => "c".to_string(),
+            CoverFit::Contain { pad: Some([r, g, b, a]) } => {
+                format!("c{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+            }
+        };
+
+        let quality = if quality == default_quality {
+            String::new()
+        } else {
+            format!("q{}", quality)
+        };
+
+        if dim == default_dim && fit.is_empty() && quality.is_empty() {
+            hash.to_string()
+        } else {
+            format!("{}_{}{}{}", hash, dim, fit, quality)
+        }
+    }
+
+    /// Resize an image to a square variant using the configured fit
+    fn resize_variant(&self, img: &DynamicImage, dim: u32) -> DynamicImage {
+        // Use faster filter
+        let filter = image::imageops::FilterType::Triangle;
+
+        match self.fit {
+            CoverFit::Fill => img.resize_to_fill(dim, dim, filter),
+            CoverFit::Contain { pad: None } => img.resize(dim, dim, filter),
+            CoverFit::Contain { pad: Some(color) } => {
+                let resized = img.resize(dim, dim, filter).to_rgba8();
+                let mut canvas = image::RgbaImage::from_pixel(dim, dim, image::Rgba(color));
+                let x = (dim - resized.width()) / 2;
+                let y = (dim - resized.height()) / 2;
+                image::imageops::overlay(&mut canvas, &resized, x as i64, y as i64);
+                DynamicImage::ImageRgba8(canvas)
+            }
+        }
     }
 
-    /// Ensure cache directories exist
+    /// Get the path for the configured variant of a hash
+    fn variant_path(&self, hash: &str, size: CoverSize) -> PathBuf {
+        let ext = self
+            .variant_format(size)
+            .map(|f| f.extension())
+            .unwrap_or("jpg");
+        self.cover_file(hash, size, &format!("{}.{}", self.variant_stem(hash, size), ext))
+    }
+
+    /// Ensure cache directories exist in the primary root. Fallback roots are
+    /// only read from, and may be offline (e.g. an unmounted archive disk)
     pub fn ensure_dirs(&self) -> std::io::Result<()> {
-        fs::create_dir_all(self.size_dir(CoverSize::Small))?;
-        fs::create_dir_all(self.size_dir(CoverSize::Mid))?;
-        fs::create_dir_all(self.size_dir(CoverSize::Original))?;
+        fs::create_dir_all(size_dir_in(&self.cache_dir, CoverSize::Small))?;
+        fs::create_dir_all(size_dir_in(&self.cache_dir, CoverSize::Mid))?;
+        fs::create_dir_all(size_dir_in(&self.cache_dir, CoverSize::Original))?;
         Ok(())
     }
 
@@ -73,76 +703,496 @@ impl CoverCache {
     /// Save cover to cache (small, mid, and original)
     /// Returns the cover hash
     pub fn save_cover(&self, data: &[u8], mime_type: Option<&str>) -> Result<String, String> {
+        self.save_cover_detailed(data, mime_type).map(|saved| saved.hash)
+    }
+
+    /// Save cover to cache (small, mid, and original)
+    /// Returns the cover hash along with the original's dimensions and format
+    pub fn save_cover_detailed(
+        &self,
+        data: &[u8],
+        mime_type: Option<&str>,
+    ) -> Result<SavedCover, String> {
         let hash = Self::hash_cover(data);
 
-        // Check if already cached
-        let mid_path = self.cover_path(&hash, CoverSize::Mid, "jpg");
-        if mid_path.exists() {
-            return Ok(hash);
+        // Determine extension from mime type for original
+        let ext = original_extension(data, mime_type);
+
+        // Reject tiny thumbnails from the header alone, before decoding
+        if let Some(min) = self.min_original_dimension {
+            if let Ok((width, height)) = image_dimensions(data, ext) {
+                if width.min(height) < min {
+                    return Err(format!("{}: {}x{}", TOO_SMALL_ERROR, width, height));
+                }
+            }
         }
 
-        // Determine extension from mime type for original
-        let ext = match mime_type {
-            Some("image/png") => "png",
-            Some("image/gif") => "gif",
-            Some("image/webp") => "webp",
-            _ => "jpg",
+        // Check if already cached (only read the image header for dimensions)
+        let marker = self.marker_size();
+        if self.get_cover_path(&hash, marker).is_some() {
+            let blurhash = self.get_blurhash(&hash);
+            return self.cached_cover_info(hash, data, ext, blurhash);
+        }
+
+        // Serialize work per hash; another thread may have finished it meanwhile
+        let _guard = self.hash_lock(&hash).lock().unwrap_or_else(|e| e.into_inner());
+        if self.find_cover_path(&hash, marker).is_some() {
+            let blurhash = self.read_blurhash(&hash);
+            return self.cached_cover_info(hash, data, ext, blurhash);
+        }
+
+        let (width, height, orig_data, orig_ext, blurhash) = self.in_pool(|| {
+            // Decode image
+            let img = decode_image(data, ext)?;
+            let (width, height) = img.dimensions();
+
+            // A near-duplicate of a cached cover only gets an alias file
+            if self.perceptual_dedupe {
+                if let Some(canonical) = self.find_or_index_duplicate(&hash, &img) {
+                    write_atomic(&self.alias_path(&hash), canonical.as_bytes())?;
+                    let blurhash = self.get_blurhash(&canonical).unwrap_or_default();
+                    return Ok((width, height, data.to_vec(), ext, blurhash));
+                }
+            }
+
+            // Save original (verbatim, or re-encoded without metadata, which
+            // applies the EXIF orientation and so may swap width and height)
+            let (orig_data, orig_ext) = if self.strip_metadata {
+                self.strip_original(data, &img, ext)?
+            } else {
+                (data.to_vec(), ext)
+            };
+            let (width, height) = if self.strip_metadata {
+                image_dimensions(&orig_data, orig_ext)?
+            } else {
+                (width, height)
+            };
+            if self.generates(CoverSize::Original) {
+                let orig_path = self.cover_path(&hash, CoverSize::Original, orig_ext);
+                if let Some(parent) = orig_path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                write_atomic(&orig_path, &orig_data)?;
+            }
+
+            let blurhash = self.write_variants(&hash, &img)?;
+            Ok::<_, String>((width, height, orig_data, orig_ext, blurhash))
+        })?;
+
+        self.written
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(hash.clone());
+        drop(_guard);
+
+        // Opportunistically keep the cache under its size cap
+        if self.max_bytes.is_some()
+            && self.writes_since_enforce.fetch_add(1, Ordering::Relaxed) + 1 >= ENFORCE_INTERVAL
+        {
+            self.writes_since_enforce.store(0, Ordering::Relaxed);
+            self.enforce_limit();
+        }
+
+        Ok(SavedCover {
+            hash,
+            width,
+            height,
+            orig_format: orig_ext.to_string(),
+            orig_bytes: orig_data.len() as u64,
+            blurhash: Some(blurhash),
+        })
+    }
+
+    /// Build the details of an already cached cover. They describe the cached
+    /// original when there is one, as the first save did (it may have been
+    /// re-encoded by `strip_metadata`), and the incoming data otherwise
+    fn cached_cover_info(
+        &self,
+        hash: String,
+        data: &[u8],
+        ext: &str,
+        blurhash: Option<String>,
+    ) -> Result<SavedCover, String> {
+        let cached = self.find_cover_path(&hash, CoverSize::Original).and_then(|path| {
+            let orig_ext = path.extension().and_then(|e| e.to_str())?.to_string();
+            let orig_bytes = fs::metadata(&path).ok()?.len();
+            // Only the header is read, except for formats image can't probe
+            let dims = image::ImageReader::open(&path)
+                .ok()
+                .and_then(|reader| reader.with_guessed_format().ok())
+                .and_then(|reader| reader.into_dimensions().ok())
+                .or_else(|| image_dimensions(&fs::read(&path).ok()?, &orig_ext).ok())?;
+            Some((orig_ext, orig_bytes, dims))
+        });
+        let (orig_format, orig_bytes, (width, height)) = match cached {
+            Some(cached) => cached,
+            None => (ext.to_string(), data.len() as u64, image_dimensions(data, ext)?),
         };
+        Ok(SavedCover {
+            hash,
+            width,
+            height,
+            orig_format,
+            orig_bytes,
+            blurhash,
+        })
+    }
 
-        // Decode image
-        let img = image::load_from_memory(data)
-            .map_err(|e| format!("Failed to decode image: {}", e))?;
+    /// Re-encode a decoded original without its metadata, keeping its format
+    /// where an encoder is available (PNG otherwise). The EXIF orientation is
+    /// applied first, since the re-encoded file no longer carries it.
+    /// Returns the encoded data and its extension
+    fn strip_original(
+        &self,
+        data: &[u8],
+        img: &DynamicImage,
+        ext: &str,
+    ) -> Result<(Vec<u8>, &'static str), String> {
+        let oriented;
+        let img = match exif_orientation(data) {
+            Some(orientation) if orientation != Orientation::NoTransforms => {
+                let mut copy = img.clone();
+                copy.apply_orientation(orientation);
+                oriented = copy;
+                &oriented
+            }
+            _ => img,
+        };
 
-        // Save original
-        let orig_path = self.cover_path(&hash, CoverSize::Original, ext);
-        if let Some(parent) = orig_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        match ext {
+            "jpg" => {
+                let data = if self.progressive {
+                    encode_progressive_jpeg(img, STRIPPED_QUALITY)?
+                } else {
+                    encode_jpeg(img, STRIPPED_QUALITY)?
+                };
+                Ok((data, "jpg"))
+            }
+            "webp" => Ok((encode_webp(img, is_lossless_webp(data)), "webp")),
+            "bmp" => Ok((encode_with_format(img, image::ImageFormat::Bmp)?, "bmp")),
+            "tiff" => Ok((encode_with_format(img, image::ImageFormat::Tiff)?, "tiff")),
+            _ => Ok((encode_with_format(img, image::ImageFormat::Png)?, "png")),
         }
-        fs::write(&orig_path, data).map_err(|e| e.to_string())?;
+    }
 
-        // Create and save mid (300x300) - use faster filter
-        let mid_img = img.resize_to_fill(300, 300, image::imageops::FilterType::Triangle);
-        if let Some(parent) = mid_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    /// Create and save the small and mid variants of a decoded cover,
+    /// along with the BlurHash sidecar. Returns the BlurHash
+    fn write_variants(&self, hash: &str, img: &DynamicImage) -> Result<String, String> {
+        // Create and save small
+        let small_img = self.resize_variant(img, self.small_size);
+        if self.generates(CoverSize::Small) {
+            let small_path = self.variant_path(hash, CoverSize::Small);
+            if let Some(parent) = small_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            save_variant(&small_img, &small_path, self.small_format, false)?;
         }
-        save_as_jpeg(&mid_img, &mid_path, 85)?;
 
-        // Create and save small (120x120) - use faster filter
-        let small_path = self.cover_path(&hash, CoverSize::Small, "jpg");
-        let small_img = img.resize_to_fill(120, 120, image::imageops::FilterType::Triangle);
-        if let Some(parent) = small_path.parent() {
+        // The BlurHash sidecar lives in the mid directory even without a mid variant
+        let blurhash_path = self.blurhash_path(hash);
+        if let Some(parent) = blurhash_path.parent() {
             fs::create_dir_all(parent).map_err(|e| e.to_string())?;
         }
-        save_as_jpeg(&small_img, &small_path, 80)?;
+        if !self.generates(CoverSize::Mid) {
+            let blurhash = compute_blurhash(&small_img);
+            write_atomic(&blurhash_path, blurhash.as_bytes())?;
+            return Ok(blurhash);
+        }
+
+        // Create and save mid last, since its presence marks the cover as cached
+        let mid_path = self.variant_path(hash, CoverSize::Mid);
+        let mid_img = self.resize_variant(img, self.mid_size);
+        let blurhash = compute_blurhash(&mid_img);
+        write_atomic(&blurhash_path, blurhash.as_bytes())?;
+        save_variant(&mid_img, &mid_path, self.mid_format, self.progressive)?;
+
+        Ok(blurhash)
+    }
+
+    /// Get the path of the BlurHash sidecar, stored next to the mid variant
+    fn blurhash_path(&self, hash: &str) -> PathBuf {
+        let stem = self.variant_stem(hash, CoverSize::Mid);
+        self.cover_file(hash, CoverSize::Mid, &format!("{}.bh", stem))
+    }
+
+    /// Read a cover's BlurHash sidecar
+    fn read_blurhash(&self, hash: &str) -> Option<String> {
+        let stem = self.variant_stem(hash, CoverSize::Mid);
+        let path = self.find_file(hash, CoverSize::Mid, &format!("{}.bh", stem))?;
+        fs::read_to_string(path)
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Get a cover's BlurHash for placeholder rendering. Covers cached before
+    /// BlurHashes were generated get one computed from the mid variant on demand
+    pub fn get_blurhash(&self, hash: &str) -> Option<String> {
+        if let Some(blurhash) = self.read_blurhash(hash) {
+            return Some(blurhash);
+        }
+        if let Some(canonical) = self.alias_target(hash) {
+            return self.get_blurhash(&canonical);
+        }
+
+        let mid_path = self.get_cover_path(hash, CoverSize::Mid)?;
+        let data = fs::read(&mid_path).ok()?;
+        let ext = mid_path.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+        let blurhash = compute_blurhash(&decode_image(&data, ext).ok()?);
+
+        let _ = write_atomic(&self.blurhash_path(hash), blurhash.as_bytes());
+        Some(blurhash)
+    }
+
+    /// Recreate the small and mid variants of a cover from its cached
+    /// original, without touching the source audio files
+    pub fn regenerate_variants(&self, hash: &str) -> Result<(), String> {
+        if let Some(canonical) = self.alias_target(hash) {
+            return self.regenerate_variants(&canonical);
+        }
+        let _guard = self.hash_lock(hash).lock().unwrap_or_else(|e| e.into_inner());
+
+        let orig_path = self
+            .get_cover_path(hash, CoverSize::Original)
+            .ok_or_else(|| format!("Original cover not cached: {}", hash))?;
+        let data = fs::read(&orig_path).map_err(|e| format!("Failed to read cover: {}", e))?;
+        let ext = orig_path.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+
+        self.in_pool(|| {
+            let img = decode_image(&data, ext)?;
+            self.write_variants(hash, &img).map(|_| ())
+        })
+    }
+
+    /// Get cover file path when the caller already knows its extension
+    /// (e.g. `SavedCover::orig_format`): a hit costs a single stat, and
+    /// misses fall back to probing like `get_cover_path`
+    pub fn get_cover_path_with_ext(
+        &self,
+        hash: &str,
+        size: CoverSize,
+        ext: Option<&str>,
+    ) -> Option<PathBuf> {
+        if let Some(ext) = ext.map(|e| e.trim_start_matches('.').to_lowercase()) {
+            let stem = match self.variant_format(size) {
+                Some(format) if format.extension() == ext => self.variant_stem(hash, size),
+                _ => hash.to_string(),
+            };
+            if let Some(path) = self.find_file(hash, size, &format!("{}.{}", stem, ext)) {
+                return Some(path);
+            }
+        }
+        self.get_cover_path(hash, size)
+    }
 
-        Ok(hash)
+    /// Get cover URL (asset protocol) by hash, size and known extension
+    pub fn get_cover_url_with_ext(
+        &self,
+        hash: &str,
+        size: CoverSize,
+        ext: Option<&str>,
+    ) -> Option<String> {
+        self.get_cover_path_with_ext(hash, size, ext).map(|path| asset_url(&path))
     }
 
     /// Get cover file path by hash and size
+    /// (following the alias of a near-duplicate cover)
     pub fn get_cover_path(&self, hash: &str, size: CoverSize) -> Option<PathBuf> {
-        // Try common extensions
-        for ext in &["jpg", "png", "webp", "gif"] {
-            let path = self.cover_path(hash, size, ext);
-            if path.exists() {
-                return Some(path);
+        self.find_cover_path(hash, size).or_else(|| {
+            let canonical = self.alias_target(hash)?;
+            self.find_cover_path(&canonical, size)
+        })
+    }
+
+    /// Get the cached file of exactly this hash and size, checking every
+    /// root in order
+    fn find_cover_path(&self, hash: &str, size: CoverSize) -> Option<PathBuf> {
+        let prefix = &hash[..2.min(hash.len())];
+        let stem = self.variant_stem(hash, size);
+        let variant = self
+            .variant_format(size)
+            .map(|format| format!("{}.{}", stem, format.extension()));
+        // A plain `{hash}` variant was made at the default size, fit and quality,
+        // so it only stands in for variants configured that way
+        let plain_fallback = size == CoverSize::Original || stem == hash;
+
+        for root in self.roots() {
+            let dir = size_dir_in(root, size).join(prefix);
+
+            // Try the configured variant first
+            if let Some(variant) = &variant {
+                let path = dir.join(variant);
+                if path.exists() {
+                    return Some(path);
+                }
+            }
+
+            // Try common extensions (also finds variants cached in a previous format)
+            if !plain_fallback {
+                continue;
+            }
+            for ext in ORIGINAL_EXTENSIONS {
+                let path = dir.join(format!("{}.{}", hash, ext));
+                if path.exists() {
+                    return Some(path);
+                }
             }
         }
         None
     }
 
+    /// Read cached cover bytes by hash and size
+    /// Returns the raw file contents together with its mime type
+    pub fn get_cover_bytes(&self, hash: &str, size: CoverSize) -> Option<(Vec<u8>, &'static str)> {
+        let path = self.get_cover_path(hash, size)?;
+        let data = fs::read(&path).ok()?;
+        let mime = mime_from_extension(path.extension().and_then(|e| e.to_str()).unwrap_or(""));
+        Some((data, mime))
+    }
+
+    /// Copy a cached cover to `out_path`, e.g. for "Save cover as...".
+    /// The extension of `out_path` is replaced with the cached file's, so the
+    /// export always matches its format (originals are copied byte for byte).
+    /// Returns the path written
+    pub fn export_cover(
+        &self,
+        hash: &str,
+        size: CoverSize,
+        out_path: &Path,
+    ) -> Result<PathBuf, String> {
+        let path = self
+            .get_cover_path(hash, size)
+            .ok_or_else(|| format!("Cover not cached: {}", hash))?;
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+        let out_path = out_path.with_extension(ext);
+
+        if let Some(parent) = out_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        fs::copy(&path, &out_path).map_err(|e| format!("Failed to export cover: {}", e))?;
+        Ok(out_path)
+    }
+
     /// Get cover URL (asset protocol) by hash and size
     /// Uses http://asset.localhost/ format for Tauri 2.0
     pub fn get_cover_url(&self, hash: &str, size: CoverSize) -> Option<String> {
-        self.get_cover_path(hash, size).map(|path| {
-            let path_str = path.to_string_lossy().replace('\\', "/");
-            // URL encode the colon in Windows drive letter (C: -> C%3A)
-            let encoded_path = if path_str.len() > 1 && path_str.chars().nth(1) == Some(':') {
-                format!("{}%3A{}", &path_str[0..1], &path_str[2..])
+        self.get_cover_path(hash, size).map(|path| asset_url(&path))
+    }
+
+    /// Pack the small variants of `hashes` into atlas images, `columns`
+    /// cells per row, so a grid can render from a few requests.
+    /// Covers without a cached small variant are left out. A sheet never
+    /// exceeds the largest dimension the format can encode; covers that
+    /// don't fit spill onto further sheets. Sheets are keyed by their
+    /// contents and only rebuilt when their set of covers changes.
+    /// Returns each sheet's path with its packed covers' cells
+    pub fn build_sprite_sheets(
+        &self,
+        hashes: &[String],
+        columns: u32,
+    ) -> Result<Vec<(PathBuf, Vec<SpriteRect>)>, String> {
+        let cell = self.small_size.max(1);
+        let max_cells = (self.small_format.max_dimension() / cell).max(1);
+        let columns = columns.clamp(1, max_cells);
+        let per_sheet = columns as usize * max_cells as usize;
+        let present: Vec<(&String, PathBuf)> = hashes
+            .iter()
+            .filter_map(|hash| Some((hash, self.get_cover_path(hash, CoverSize::Small)?)))
+            .collect();
+
+        let sprite_dir = self.cache_dir.join("sprites");
+        let sheets = present
+            .chunks(per_sheet)
+            .map(|page| self.build_sprite_page(page, columns, cell, &sprite_dir))
+            .collect::<Result<Vec<_>, String>>()?;
+        let current: Vec<&Path> = sheets.iter().map(|(path, _)| path.as_path()).collect();
+        prune_sprite_sheets(&sprite_dir, &current);
+        Ok(sheets)
+    }
+
+    /// Build (or reuse) a single sprite sheet holding every cover of `page`
+    fn build_sprite_page(
+        &self,
+        page: &[(&String, PathBuf)],
+        columns: u32,
+        cell: u32,
+        sprite_dir: &Path,
+    ) -> Result<(PathBuf, Vec<SpriteRect>), String> {
+        let rects: Vec<SpriteRect> = page
+            .iter()
+            .enumerate()
+            .map(|(i, (hash, _))| SpriteRect {
+                hash: hash.to_string(),
+                x: (i as u32 % columns) * cell,
+                y: (i as u32 / columns) * cell,
+                width: cell,
+                height: cell,
+            })
+            .collect();
+
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}:{}:{}", columns, cell, self.small_format.extension()));
+        for (hash, _) in page {
+            hasher.update(hash.as_bytes());
+        }
+        let key = format!("{:x}", hasher.finalize());
+        let path = sprite_dir.join(format!("{}.{}", key, self.small_format.extension()));
+        if path.exists() {
+            return Ok((path, rects));
+        }
+
+        let rows = (page.len() as u32).div_ceil(columns).max(1);
+        let width = columns.min(page.len().max(1) as u32) * cell;
+        let mut sheet = image::RgbaImage::new(width, rows * cell);
+        for ((_, small_path), rect) in page.iter().zip(&rects) {
+            let Ok(data) = fs::read(small_path) else {
+                continue;
+            };
+            let ext = small_path.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+            let Ok(img) = decode_image(&data, ext) else {
+                continue;
+            };
+            // Variants cached at another size are scaled to fit the cell;
+            // non-square ones (CoverFit::Contain) are centered in it
+            let img = if img.width().max(img.height()) != cell {
+                img.resize(cell, cell, image::imageops::FilterType::Triangle)
             } else {
-                path_str
+                img
             };
-            format!("http://asset.localhost/{}", encoded_path)
-        })
+            let img = img.to_rgba8();
+            let x = rect.x + cell.saturating_sub(img.width()) / 2;
+            let y = rect.y + cell.saturating_sub(img.height()) / 2;
+            image::imageops::overlay(&mut sheet, &img, x as i64, y as i64);
+        }
+
+        fs::create_dir_all(sprite_dir).map_err(|e| e.to_string())?;
+        let sheet = DynamicImage::ImageRgba8(sheet);
+        self.in_pool(|| save_variant(&sheet, &path, self.small_format, false))?;
+        Ok((path, rects))
+    }
+
+    /// Get the user-chosen covers that override embedded art, by album ID
+    pub fn album_cover_overrides(&self) -> HashMap<String, String> {
+        fs::read_to_string(self.cache_dir.join(ALBUM_COVERS_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Set (or with `None`, remove) the preferred cover of an album
+    pub fn set_album_cover_override(
+        &self,
+        album_id: &str,
+        hash: Option<&str>,
+    ) -> Result<(), String> {
+        let _guard = self.album_covers_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut overrides = self.album_cover_overrides();
+        match hash {
+            Some(hash) => overrides.insert(album_id.to_string(), hash.to_string()),
+            None => overrides.remove(album_id),
+        };
+        let content = serde_json::to_string_pretty(&overrides).map_err(|e| e.to_string())?;
+        write_atomic(&self.cache_dir.join(ALBUM_COVERS_FILE), content.as_bytes())
     }
 
     /// Check if a cover exists in cache
@@ -151,12 +1201,68 @@ impl CoverCache {
         self.get_cover_path(hash, CoverSize::Mid).is_some()
     }
 
-    /// Get cache statistics
+    /// Get the path of the palette sidecar, stored next to the small variant
+    fn palette_path(&self, hash: &str) -> PathBuf {
+        let stem = self.variant_stem(hash, CoverSize::Small);
+        self.cover_file(hash, CoverSize::Small, &format!("{}.pal", stem))
+    }
+
+    /// Get a cover's most prominent colors (most prominent first), e.g. for
+    /// tinting the now-playing background. Computed from the small variant
+    /// once and cached in a sidecar file (one hex color per line)
+    pub fn palette(&self, hash: &str) -> Option<Vec<[u8; 3]>> {
+        let path = self.palette_path(hash);
+        let stem = self.variant_stem(hash, CoverSize::Small);
+        let cached = self.find_file(hash, CoverSize::Small, &format!("{}.pal", stem));
+        if let Some(content) = cached.and_then(|p| fs::read_to_string(p).ok()) {
+            let colors: Vec<[u8; 3]> = content.lines().filter_map(parse_hex_color).collect();
+            if !colors.is_empty() {
+                return Some(colors);
+            }
+        }
+        if let Some(canonical) = self.alias_target(hash) {
+            return self.palette(&canonical);
+        }
+
+        let small_path = self.get_cover_path(hash, CoverSize::Small)?;
+        let data = fs::read(&small_path).ok()?;
+        let ext = small_path.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+        let img = decode_image(&data, ext).ok()?.to_rgb8();
+
+        let colors = palette::extract(&img, PALETTE_SIZE);
+        if colors.is_empty() {
+            return None;
+        }
+
+        let content: Vec<String> = colors
+            .iter()
+            .map(|[r, g, b]| format!("{:02x}{:02x}{:02x}", r, g, b))
+            .collect();
+        let _ = write_atomic(&path, content.join("\n").as_bytes());
+        Some(colors)
+    }
+
+    /// Get a cover's dominant (most prominent non-background) color
+    pub fn dominant_color(&self, hash: &str) -> Option<[u8; 3]> {
+        self.palette(hash)?.first().copied()
+    }
+
+    /// Check which variants of a cover are cached, e.g. to detect covers
+    /// whose original or thumbnails were pruned
+    pub fn cover_variants(&self, hash: &str) -> CoverVariants {
+        CoverVariants {
+            small: self.get_cover_path(hash, CoverSize::Small).is_some(),
+            mid: self.get_cover_path(hash, CoverSize::Mid).is_some(),
+            orig: self.get_cover_path(hash, CoverSize::Original).is_some(),
+        }
+    }
+
+    /// Get cache statistics (summed over every root)
     pub fn get_stats(&self) -> CacheStats {
         let mut stats = CacheStats::default();
 
-        for size in [CoverSize::Small, CoverSize::Mid, CoverSize::Original] {
-            let dir = self.size_dir(size);
+        let sizes = [CoverSize::Small, CoverSize::Mid, CoverSize::Original];
+        for dir in sizes.into_iter().flat_map(|size| self.size_dirs(size)) {
             if let Ok(entries) = fs::read_dir(&dir) {
                 for entry in entries.flatten() {
                     if entry.path().is_dir() {
@@ -176,57 +1282,273 @@ impl CoverCache {
         stats
     }
 
-    /// Clean up orphaned covers (covers not referenced by any song)
-    pub fn cleanup_orphaned(&self, valid_hashes: &[String]) -> Result<usize, String> {
-        let valid_set: std::collections::HashSet<_> = valid_hashes.iter().collect();
-        let mut removed = 0;
+    /// List every cached file (all sizes) with its size and last use time.
+    /// Only the primary root counts towards `max_bytes`
+    fn cached_files(&self) -> Vec<CachedFile> {
+        let mut files = Vec::new();
 
         for size in [CoverSize::Small, CoverSize::Mid, CoverSize::Original] {
-            let dir = self.size_dir(size);
-            if let Ok(entries) = fs::read_dir(&dir) {
-                for entry in entries.flatten() {
-                    if entry.path().is_dir() {
-                        if let Ok(sub_entries) = fs::read_dir(entry.path()) {
-                            for sub_entry in sub_entries.flatten() {
-                                let path = sub_entry.path();
-                                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                                    if !valid_set.contains(&stem.to_string()) {
-                                        if fs::remove_file(&path).is_ok() {
-                                            removed += 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+            let Ok(entries) = fs::read_dir(self.size_dir(size)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if !entry.path().is_dir() {
+                    continue;
+                }
+                let Ok(sub_entries) = fs::read_dir(entry.path()) else {
+                    continue;
+                };
+                for sub_entry in sub_entries.flatten() {
+                    let path = sub_entry.path();
+                    let (Some(hash), Ok(meta)) = (hash_from_path(&path), sub_entry.metadata())
+                    else {
+                        continue;
+                    };
+                    // atime is often disabled (noatime), so take the later of atime/mtime
+                    let last_used = [meta.accessed().ok(), meta.modified().ok()]
+                        .into_iter()
+                        .flatten()
+                        .max()
+                        .unwrap_or(SystemTime::UNIX_EPOCH);
+                    files.push(CachedFile {
+                        hash: hash.to_string(),
+                        path,
+                        size: meta.len(),
+                        last_used,
+                    });
                 }
             }
         }
 
-        Ok(removed)
+        files
     }
 
-    /// Clear all cached covers
-    pub fn clear_all(&self) -> Result<usize, String> {
+    /// Evict least recently used covers (original together with its small/mid
+    /// variants) until the cache fits within `max_bytes`. Covers written in the
+    /// current run are never evicted; files that can't be removed are skipped.
+    /// Returns the number of files removed
+    pub fn enforce_limit(&self) -> usize {
+        let Some(max_bytes) = self.max_bytes else {
+            return 0;
+        };
+
+        let files = self.cached_files();
+        let mut total: u64 = files.iter().map(|f| f.size).sum();
+        if total <= max_bytes {
+            return 0;
+        }
+
+        // Group files by hash; a cover's last use is its most recently used file
+        let mut covers: HashMap<String, (SystemTime, Vec<CachedFile>)> = HashMap::new();
+        for file in files {
+            let cover = covers
+                .entry(file.hash.clone())
+                .or_insert((SystemTime::UNIX_EPOCH, Vec::new()));
+            cover.0 = cover.0.max(file.last_used);
+            cover.1.push(file);
+        }
+
+        let written = self.written.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let mut candidates: Vec<_> = covers
+            .into_iter()
+            .filter(|(hash, _)| !written.contains(hash))
+            .collect();
+        candidates.sort_by_key(|(_, (last_used, _))| *last_used);
+
         let mut removed = 0;
+        for (hash, (_, files)) in candidates {
+            if total <= max_bytes {
+                break;
+            }
+            let _guard = self.hash_lock(&hash).lock().unwrap_or_else(|e| e.into_inner());
+            for file in files {
+                if fs::remove_file(&file.path).is_ok() {
+                    total = total.saturating_sub(file.size);
+                    removed += 1;
+                }
+            }
+        }
 
-        for size in [CoverSize::Small, CoverSize::Mid, CoverSize::Original] {
-            let dir = self.size_dir(size);
-            if dir.exists() {
-                if let Ok(entries) = fs::read_dir(&dir) {
-                    for entry in entries.flatten() {
-                        if entry.path().is_dir() {
-                            if let Ok(count) = fs::read_dir(entry.path()).map(|e| e.count()) {
-                                removed += count;
+        removed
+    }
+
+    /// List the hash prefix directories of every size, in every root
+    fn prefix_dirs(&self) -> Vec<PathBuf> {
+        [CoverSize::Small, CoverSize::Mid, CoverSize::Original]
+            .into_iter()
+            .flat_map(|size| self.size_dirs(size))
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect()
+    }
+
+    /// Find and remove corrupt cache entries, e.g. zero-length or truncated
+    /// files left behind by an unclean shutdown. Files are checked by size and
+    /// a cheap header/trailer check; only suspect images are fully decoded.
+    /// Every file of an affected cover is removed so it can be re-extracted.
+    /// Returns the affected hashes
+    pub fn verify(&self) -> Vec<String> {
+        let paths: Vec<PathBuf> = self
+            .prefix_dirs()
+            .par_iter()
+            .flat_map_iter(|dir| {
+                fs::read_dir(dir)
+                    .into_iter()
+                    .flat_map(|entries| entries.flatten())
+                    .map(|entry| entry.path())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let corrupt: HashSet<String> = paths
+            .par_iter()
+            .filter_map(|path| {
+                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                if ext == "tmp" {
+                    remove_stale_tmp(path);
+                    return None;
+                }
+                let hash = hash_from_path(path)?;
+                is_corrupt(path, ext).then(|| hash.to_string())
+            })
+            .collect();
+
+        let mut by_hash: HashMap<&str, Vec<&PathBuf>> = HashMap::new();
+        for path in &paths {
+            if let Some(hash) = hash_from_path(path).filter(|h| corrupt.contains(*h)) {
+                by_hash.entry(hash).or_default().push(path);
+            }
+        }
+        for (hash, paths) in by_hash {
+            let _guard = self.hash_lock(hash).lock().unwrap_or_else(|e| e.into_inner());
+            for path in paths {
+                let _ = fs::remove_file(path);
+            }
+        }
+
+        let mut hashes: Vec<String> = corrupt.into_iter().collect();
+        hashes.sort();
+        hashes
+    }
+
+    /// Clean up orphaned covers (covers not referenced by any song).
+    /// All variants of an orphaned hash are removed together.
+    /// An empty `valid_hashes` would wipe the whole cache, so it is rejected
+    /// unless `confirm_empty` is set. With `dry_run` nothing is deleted.
+    /// Returns the paths of the files removed (or that would be removed)
+    pub fn cleanup_orphaned(
+        &self,
+        valid_hashes: &[String],
+        confirm_empty: bool,
+        dry_run: bool,
+    ) -> Result<Vec<String>, String> {
+        if valid_hashes.is_empty() && !confirm_empty {
+            return Err(
+                "Refusing to clean up covers: no valid hashes given (pass confirmEmpty to remove all)"
+                    .to_string(),
+            );
+        }
+
+        let mut valid_set: HashSet<String> = valid_hashes.iter().cloned().collect();
+        // Album cover overrides aren't referenced by any song
+        valid_set.extend(self.album_cover_overrides().into_values());
+        // Keep the canonical covers that referenced near-duplicates point at
+        let canonicals: Vec<String> =
+            valid_set.par_iter().filter_map(|h| self.alias_target(h)).collect();
+        valid_set.extend(canonicals);
+
+        // Collect removal candidates first, traversing prefix directories in parallel
+        let candidates: Vec<(String, PathBuf)> = self
+            .prefix_dirs()
+            .par_iter()
+            .flat_map_iter(|dir| {
+                fs::read_dir(dir)
+                    .into_iter()
+                    .flat_map(|entries| entries.flatten())
+                    .filter_map(|entry| {
+                        let path = entry.path();
+                        // In-flight writes belong to their writer; only stale ones go
+                        if path.extension().is_some_and(|e| e == "tmp") {
+                            if !dry_run {
+                                remove_stale_tmp(&path);
                             }
-                            let _ = fs::remove_dir_all(entry.path());
+                            return None;
                         }
-                    }
-                }
+                        let hash = hash_from_path(&path)?;
+                        if valid_set.contains(hash) {
+                            None
+                        } else {
+                            Some((hash.to_string(), path))
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let mut orphaned: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for (hash, path) in candidates {
+            orphaned.entry(hash).or_default().push(path);
+        }
+
+        if dry_run {
+            return Ok(sorted_paths(orphaned.into_values().flatten()));
+        }
+
+        // Then delete every variant of each orphaned hash
+        let removed: Vec<PathBuf> = orphaned
+            .par_iter()
+            .flat_map_iter(|(hash, paths)| {
+                let _guard = self.hash_lock(hash).lock().unwrap_or_else(|e| e.into_inner());
+                paths
+                    .iter()
+                    .filter(|path| fs::remove_file(path).is_ok())
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        Ok(sorted_paths(removed))
+    }
+
+    /// Clear all cached covers (in every root). With `dry_run` nothing is
+    /// deleted. Returns the paths of the files removed (or that would be removed)
+    pub fn clear_all(&self, dry_run: bool) -> Result<Vec<String>, String> {
+        let sprite_dir = self.cache_dir.join("sprites");
+        let mut dirs = self.prefix_dirs();
+        dirs.push(sprite_dir);
+
+        let mut removed = Vec::new();
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            removed.extend(entries.flatten().map(|entry| entry.path()));
+            if !dry_run {
+                let _ = fs::remove_dir_all(&dir);
             }
         }
 
-        Ok(removed)
+        Ok(sorted_paths(removed))
+    }
+}
+
+/// Convert paths to sorted strings for reporting
+fn sorted_paths(paths: impl IntoIterator<Item = PathBuf>) -> Vec<String> {
+    let mut paths: Vec<String> = paths
+        .into_iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Get the cache directory for a given size under `root`
+fn size_dir_in(root: &Path, size: CoverSize) -> PathBuf {
+    match size {
+        CoverSize::Small => root.join("small"),
+        CoverSize::Mid => root.join("mid"),
+        CoverSize::Original => root.join("orig"),
     }
 }
 
@@ -237,8 +1559,417 @@ pub struct CacheStats {
     pub total_size: u64,
 }
 
+/// A file in the cover cache
+struct CachedFile {
+    hash: String,
+    path: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+}
+
+/// Hashes in a dHash index slice whose dHash is close to `dhash`, besides `hash` itself
+fn dhash_candidates(entries: &[(String, u64)], hash: &str, dhash: u64) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|(other, _)| other != hash)
+        .filter(|(_, other)| (dhash ^ other).count_ones() <= DHASH_MAX_DISTANCE)
+        .map(|(other, _)| other.clone())
+        .collect()
+}
+
+/// Extract the cover hash from a cached file name, ignoring variant suffixes.
+/// Files not named after a hash (e.g. in-flight `.{file}.{uuid}.tmp` writes) give None
+fn hash_from_path(path: &Path) -> Option<&str> {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .and_then(|s| s.split('_').next())
+        .filter(|hash| is_cover_hash(hash))
+}
+
+/// Check that a string looks like a cover hash (lowercase hex SHA-256)
+pub fn is_cover_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Build an asset protocol URL for a local file, percent-encoding each path
+/// segment and keeping `/` as the separator (a Windows drive becomes `C%3A`).
+/// Encodes the raw path bytes, so non-UTF-8 file names are not mangled
+pub fn asset_url(path: &Path) -> String {
+    let bytes = path_bytes(path);
+    let encoded_path: Vec<String> = bytes
+        .split(|b| *b == b'/')
+        .map(|segment| percent_encode(segment, PATH_SEGMENT).to_string())
+        .collect();
+    format!("http://asset.localhost/{}", encoded_path.join("/"))
+}
+
+/// Keep only the most recently written sprite sheets, besides the `current` ones
+fn prune_sprite_sheets(sprite_dir: &Path, current: &[&Path]) {
+    let Ok(entries) = fs::read_dir(sprite_dir) else {
+        return;
+    };
+    let mut sheets: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|e| !current.contains(&e.path().as_path()))
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    sheets.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, path) in sheets.into_iter().skip(MAX_SPRITE_SHEETS) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Remove a temp file left behind by an interrupted write.
+/// Recent ones may belong to a write still in progress and are kept
+fn remove_stale_tmp(path: &Path) {
+    let age = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok());
+    if age.is_some_and(|age| age >= STALE_TMP_AGE) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Check whether a cached file is corrupt: empty, or an image that looks
+/// truncated and fails to decode
+fn is_corrupt(path: &Path, ext: &str) -> bool {
+    let Ok(meta) = fs::metadata(path) else {
+        return false;
+    };
+    if meta.len() == 0 {
+        return true;
+    }
+    // Sidecars (blurhash, palette) are recomputed when unreadable
+    if !ORIGINAL_EXTENSIONS.contains(&ext) || looks_intact(path, meta.len(), ext) {
+        return false;
+    }
+    fs::read(path)
+        .map(|data| decode_image(&data, ext).is_err())
+        .unwrap_or(false)
+}
+
+/// Cheap integrity check from the first and last bytes of an image file.
+/// Formats without a simple check are assumed intact
+fn looks_intact(path: &Path, len: u64, ext: &str) -> bool {
+    let read_ends = || -> std::io::Result<([u8; 12], [u8; 8])> {
+        let mut file = fs::File::open(path)?;
+        let mut head = [0u8; 12];
+        let mut tail = [0u8; 8];
+        file.read_exact(&mut head)?;
+        file.seek(SeekFrom::End(-8))?;
+        file.read_exact(&mut tail)?;
+        Ok((head, tail))
+    };
+    let Ok((head, tail)) = read_ends() else {
+        // Shorter than any valid image
+        return false;
+    };
+
+    match ext {
+        "jpg" => head.starts_with(&[0xFF, 0xD8]) && tail.ends_with(&[0xFF, 0xD9]),
+        "png" => {
+            head.starts_with(&[0x89, b'P', b'N', b'G'])
+                && tail == [b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+        }
+        "webp" => {
+            let riff_len = u32::from_le_bytes([head[4], head[5], head[6], head[7]]) as u64;
+            head.starts_with(b"RIFF") && riff_len + 8 <= len
+        }
+        _ => true,
+    }
+}
+
+/// Extensions an original cover may be stored with
+const ORIGINAL_EXTENSIONS: &[&str] =
+    &["jpg", "png", "webp", "gif", "avif", "jxl", "heic", "bmp", "tiff"];
+
+/// Determine the original cover extension from the data's magic bytes,
+/// falling back to the declared MIME type only when the format isn't recognized
+/// (tags often carry a wrong or generic MIME type)
+fn original_extension(data: &[u8], mime_type: Option<&str>) -> &'static str {
+    if is_jxl(data) {
+        return "jxl";
+    }
+    if is_heic(data) {
+        return "heic";
+    }
+    match image::guess_format(data) {
+        Ok(image::ImageFormat::Jpeg) => return "jpg",
+        Ok(image::ImageFormat::Png) => return "png",
+        Ok(image::ImageFormat::Gif) => return "gif",
+        Ok(image::ImageFormat::WebP) => return "webp",
+        Ok(image::ImageFormat::Avif) => return "avif",
+        Ok(image::ImageFormat::Bmp) => return "bmp",
+        Ok(image::ImageFormat::Tiff) => return "tiff",
+        _ => {}
+    }
+    match mime_type {
+        Some("image/png") => "png",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some("image/avif") => "avif",
+        Some("image/jxl") => "jxl",
+        Some("image/heic") | Some("image/heif") => "heic",
+        Some("image/bmp") | Some("image/x-ms-bmp") => "bmp",
+        Some("image/tiff") => "tiff",
+        _ => "jpg",
+    }
+}
+
+/// Get the mime type for a cached cover extension
+pub fn mime_from_extension(ext: &str) -> &'static str {
+    match ext {
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "jxl" => "image/jxl",
+        "heic" => "image/heic",
+        "bmp" => "image/bmp",
+        "tiff" => "image/tiff",
+        _ => "image/jpeg",
+    }
+}
+
+/// Check for a JPEG XL signature (bare codestream or ISOBMFF container)
+fn is_jxl(data: &[u8]) -> bool {
+    data.starts_with(&[0xFF, 0x0A])
+        || data.starts_with(&[0, 0, 0, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A])
+}
+
+/// Check for a HEIC/HEIF signature (ISOBMFF `ftyp` box with a HEIF brand).
+/// AVIF shares the container, so a generic `mif1` brand only counts when
+/// `avif` is not among the compatible brands
+fn is_heic(data: &[u8]) -> bool {
+    if data.len() < 12 || &data[4..8] != b"ftyp" {
+        return false;
+    }
+    match &data[8..12] {
+        b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"hevx" => true,
+        b"mif1" | b"msf1" => {
+            let box_len = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+            let brands = data.get(16..box_len.min(data.len())).unwrap_or(&[]);
+            !brands.chunks_exact(4).any(|brand| brand == b"avif" || brand == b"avis")
+        }
+        _ => false,
+    }
+}
+
+/// Decode a HEIC image with libheif
+#[cfg(feature = "heic")]
+fn decode_heic(data: &[u8]) -> Result<DynamicImage, String> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let err = |e: libheif_rs::HeifError| format!("Failed to decode HEIC image: {}", e);
+    let ctx = HeifContext::read_from_bytes(data).map_err(err)?;
+    let handle = ctx.primary_image_handle().map_err(err)?;
+    let image = LibHeif::new()
+        .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgb), None)
+        .map_err(err)?;
+
+    let plane = image
+        .planes()
+        .interleaved
+        .ok_or_else(|| "Failed to decode HEIC image: no interleaved plane".to_string())?;
+    let (width, height) = (plane.width, plane.height);
+    let row_len = width as usize * 3;
+
+    // Rows may be padded, so copy them out without the stride padding
+    let mut pixels = Vec::with_capacity(row_len * height as usize);
+    for row in plane.data.chunks(plane.stride).take(height as usize) {
+        pixels.extend_from_slice(&row[..row_len]);
+    }
+
+    image::RgbImage::from_raw(width, height, pixels)
+        .map(DynamicImage::ImageRgb8)
+        .ok_or_else(|| "Failed to decode HEIC image: invalid dimensions".to_string())
+}
+
+#[cfg(not(feature = "heic"))]
+fn decode_heic(_data: &[u8]) -> Result<DynamicImage, String> {
+    Err("HEIC support not compiled in".to_string())
+}
+
+/// Decode cover data, using the JPEG XL decoder for jxl, libheif for HEIC
+/// and image's feature-enabled decoders (including AVIF with the `avif`
+/// feature) for everything else
+fn decode_image(data: &[u8], ext: &str) -> Result<DynamicImage, String> {
+    if ext == "heic" || is_heic(data) {
+        return decode_heic(data);
+    }
+
+    if ext == "jxl" || is_jxl(data) {
+        let decoder = jxl_oxide::integration::JxlDecoder::new(Cursor::new(data))
+            .map_err(|e| format!("Failed to decode JXL image: {}", e))?;
+        return DynamicImage::from_decoder(decoder)
+            .map_err(|e| format!("Failed to decode JXL image: {}", e));
+    }
+
+    let format = image::guess_format(data)
+        .ok()
+        .or_else(|| image::ImageFormat::from_extension(ext))
+        .ok_or_else(|| format!("Failed to decode image: unrecognized format ({})", ext))?;
+    if format == image::ImageFormat::Avif && !cfg!(feature = "avif") {
+        return Err("AVIF support not compiled in".to_string());
+    }
+
+    image::load_from_memory_with_format(data, format)
+        .map_err(|e| format!("Failed to decode {:?} image: {}", format, e))
+}
+
+/// Parse a `rrggbb` hex color
+fn parse_hex_color(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.trim();
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Compute a 64-bit difference hash: compare neighbouring pixels of a 9x8
+/// grayscale thumbnail. Re-encoded or resized copies of the same art differ
+/// by at most a few bits
+fn compute_dhash(img: &DynamicImage) -> u64 {
+    let thumb = img
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    let mut dhash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = thumb.get_pixel(x, y)[0] > thumb.get_pixel(x + 1, y)[0];
+            dhash = (dhash << 1) | u64::from(brighter);
+        }
+    }
+    dhash
+}
+
+/// Mean per-channel difference (0-255) between two images, compared as
+/// small RGB thumbnails
+fn mean_pixel_diff(a: &DynamicImage, b: &DynamicImage) -> f64 {
+    let filter = image::imageops::FilterType::Triangle;
+    let a = a.resize_exact(DEDUPE_SAMPLE_SIZE, DEDUPE_SAMPLE_SIZE, filter).to_rgb8();
+    let b = b.resize_exact(DEDUPE_SAMPLE_SIZE, DEDUPE_SAMPLE_SIZE, filter).to_rgb8();
+    let total: u64 = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .map(|(x, y)| u64::from(x.abs_diff(*y)))
+        .sum();
+    total as f64 / a.as_raw().len().max(1) as f64
+}
+
+/// Compute a BlurHash from a (resized) cover image
+fn compute_blurhash(img: &DynamicImage) -> String {
+    let thumb = img
+        .resize_exact(
+            BLURHASH_INPUT_SIZE,
+            BLURHASH_INPUT_SIZE,
+            image::imageops::FilterType::Triangle,
+        )
+        .to_rgb8();
+    let (x, y) = BLURHASH_COMPONENTS;
+    blurhash::encode(&thumb, x, y)
+}
+
+/// Write a file via a temp file in the same directory and an atomic rename,
+/// so readers never observe a partially written cover
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid cache path: {}", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4()));
+
+    fs::write(&tmp_path, data).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to write file: {}", e)
+    })
+}
+
+/// Read image dimensions from the header, decoding fully only when needed
+fn image_dimensions(data: &[u8], ext: &str) -> Result<(u32, u32), String> {
+    if ext != "jxl" && ext != "heic" && !is_jxl(data) && !is_heic(data) {
+        let dims = image::ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .ok()
+            .and_then(|reader| reader.into_dimensions().ok());
+        if let Some(dims) = dims {
+            return Ok(dims);
+        }
+    }
+
+    decode_image(data, ext).map(|img| img.dimensions())
+}
+
+/// Save a resized variant in the given format
+fn save_variant(
+    img: &DynamicImage,
+    path: &Path,
+    format: CoverFormat,
+    progressive: bool,
+) -> Result<(), String> {
+    match format {
+        CoverFormat::Jpeg { quality } if progressive => save_as_progressive_jpeg(img, path, quality),
+        CoverFormat::Jpeg { quality } => save_as_jpeg(img, path, quality),
+        CoverFormat::WebP { quality } => save_as_webp(img, path, quality),
+    }
+}
+
+/// Save image as lossy WebP with quality setting (keeps transparency)
+fn save_as_webp(img: &DynamicImage, path: &Path, quality: u8) -> Result<(), String> {
+    let encoded = if img.color().has_alpha() {
+        let rgba = img.to_rgba8();
+        webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height()).encode(quality as f32)
+    } else {
+        let rgb = img.to_rgb8();
+        webp::Encoder::from_rgb(&rgb, rgb.width(), rgb.height()).encode(quality as f32)
+    };
+
+    write_atomic(path, &encoded)
+}
+
+/// Encode a stripped original as WebP, lossless when the source was
+fn encode_webp(img: &DynamicImage, lossless: bool) -> Vec<u8> {
+    let rgba = img.to_rgba8();
+    let encoder = webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height());
+    if lossless {
+        encoder.encode_lossless().to_vec()
+    } else {
+        encoder.encode(STRIPPED_QUALITY as f32).to_vec()
+    }
+}
+
+/// Check whether WebP data uses the lossless (VP8L) bitstream
+fn is_lossless_webp(data: &[u8]) -> bool {
+    data.get(12..16) == Some(b"VP8L".as_slice())
+}
+
+/// Read the EXIF orientation of encoded image data, for formats whose
+/// decoder exposes one
+fn exif_orientation(data: &[u8]) -> Option<Orientation> {
+    let mut decoder = image::ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    decoder.orientation().ok()
+}
+
 /// Save image as JPEG with quality setting
 fn save_as_jpeg(img: &DynamicImage, path: &Path, quality: u8) -> Result<(), String> {
+    write_atomic(path, &encode_jpeg(img, quality)?)
+}
+
+/// Save image as progressive JPEG with quality setting
+fn save_as_progressive_jpeg(img: &DynamicImage, path: &Path, quality: u8) -> Result<(), String> {
+    write_atomic(path, &encode_progressive_jpeg(img, quality)?)
+}
+
+/// Encode image as baseline JPEG
+fn encode_jpeg(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
     let rgb = img.to_rgb8();
     let mut buffer = Cursor::new(Vec::new());
 
@@ -247,14 +1978,38 @@ fn save_as_jpeg(img: &DynamicImage, path: &Path, quality: u8) -> Result<(), Stri
         .encode_image(&rgb)
         .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
 
-    fs::write(path, buffer.into_inner()).map_err(|e| format!("Failed to write file: {}", e))
+    Ok(buffer.into_inner())
 }
 
-/// Extract cover from audio file and cache it
-pub fn extract_and_cache_cover(
-    audio_path: &Path,
-    cache: &CoverCache,
-) -> Result<Option<String>, String> {
+/// Encode image as progressive JPEG
+/// (the `image` crate's encoder only writes baseline JPEGs)
+fn encode_progressive_jpeg(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+    let rgb = img.to_rgb8();
+    let (width, height) = (
+        u16::try_from(rgb.width()).map_err(|_| "Image too large for JPEG".to_string())?,
+        u16::try_from(rgb.height()).map_err(|_| "Image too large for JPEG".to_string())?,
+    );
+
+    let mut buffer = Vec::new();
+    let mut encoder = jpeg_encoder::Encoder::new(&mut buffer, quality);
+    encoder.set_progressive(true);
+    encoder
+        .encode(&rgb, width, height, jpeg_encoder::ColorType::Rgb)
+        .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+
+    Ok(buffer)
+}
+
+/// Encode image in one of image's lossless formats (PNG, BMP, TIFF)
+fn encode_with_format(img: &DynamicImage, format: image::ImageFormat) -> Result<Vec<u8>, String> {
+    let mut buffer = Cursor::new(Vec::new());
+    img.write_to(&mut buffer, format)
+        .map_err(|e| format!("Failed to encode {:?}: {}", format, e))?;
+    Ok(buffer.into_inner())
+}
+
+/// Read the embedded pictures of an audio file's primary (or first) tag
+fn read_pictures(audio_path: &Path) -> Result<Vec<Picture>, String> {
     use lofty::prelude::*;
     use lofty::probe::Probe;
 
@@ -267,15 +2022,86 @@ pub fn extract_and_cache_cover(
         .primary_tag()
         .or_else(|| tagged_file.first_tag());
 
-    if let Some(tag) = tag {
-        if let Some(pic) = tag.pictures().first() {
-            let mime = pic.mime_type().map(|m| m.as_str());
-            let hash = cache.save_cover(pic.data(), mime)?;
-            return Ok(Some(hash));
-        }
+    Ok(tag.map(|t| t.pictures().to_vec()).unwrap_or_default())
+}
+
+/// Pick the front cover from embedded pictures, falling back to the first one
+pub fn front_cover(pictures: &[Picture]) -> Option<&Picture> {
+    pictures
+        .iter()
+        .find(|pic| pic.pic_type() == PictureType::CoverFront)
+        .or_else(|| pictures.first())
+}
+
+/// Cache an embedded picture, returning its hash
+fn cache_picture(pic: &Picture, cache: &CoverCache) -> Result<String, String> {
+    let mime = pic.mime_type().map(|m| m.as_str());
+    cache.save_cover(pic.data(), mime)
+}
+
+/// Extract cover from audio file and cache it
+/// Prefers the front cover over other embedded pictures, and falls back to
+/// folder art (`cover.jpg`, `folder.png`, ...) next to the file
+pub fn extract_and_cache_cover(
+    audio_path: &Path,
+    cache: &CoverCache,
+) -> Result<Option<String>, String> {
+    let pictures = read_pictures(audio_path)?;
+    cache_embedded_or_folder_cover(audio_path, &pictures, cache)
+}
+
+/// Cache the front cover (or first picture) from already read pictures
+pub fn cache_front_cover(
+    pictures: &[Picture],
+    cache: &CoverCache,
+) -> Result<Option<String>, String> {
+    front_cover(pictures)
+        .map(|pic| cache_picture(pic, cache))
+        .transpose()
+}
+
+/// Cache a picture already read from an audio file's tag, falling back to
+/// folder art when there is none (avoids probing the file a second time)
+pub fn cache_embedded_or_folder_cover(
+    audio_path: &Path,
+    pictures: &[Picture],
+    cache: &CoverCache,
+) -> Result<Option<String>, String> {
+    match front_cover(pictures) {
+        // A rejected thumbnail falls back to the folder art like a missing cover
+        Some(pic) => match cache_picture(pic, cache) {
+            Err(e) if is_too_small_error(&e) => cache_folder_art(audio_path, cache),
+            result => result.map(Some),
+        },
+        None => cache_folder_art(audio_path, cache),
     }
+}
 
-    Ok(None)
+/// Whether an error from `save_cover` means the cover was below the
+/// configured minimum dimension
+pub fn is_too_small_error(error: &str) -> bool {
+    error.starts_with(TOO_SMALL_ERROR)
+}
+
+/// Cache the folder art (`cover.jpg`, `folder.png`, ...) next to an audio file
+fn cache_folder_art(audio_path: &Path, cache: &CoverCache) -> Result<Option<String>, String> {
+    let Some(art_path) = audio_path.parent().and_then(|dir| cache.find_folder_art(dir)) else {
+        return Ok(None);
+    };
+    let data = fs::read(&art_path).map_err(|e| format!("Failed to read folder art: {}", e))?;
+    cache.save_cover(&data, None).map(Some)
+}
+
+/// Extract and cache every embedded picture (front, back, artist, ...)
+/// Returns each picture's type with its cover hash
+pub fn extract_and_cache_all_covers(
+    audio_path: &Path,
+    cache: &CoverCache,
+) -> Result<Vec<(PictureType, String)>, String> {
+    read_pictures(audio_path)?
+        .iter()
+        .map(|pic| cache_picture(pic, cache).map(|hash| (pic.pic_type(), hash)))
+        .collect()
 }
 
 /// Download and cache cover from URL
@@ -284,13 +2110,32 @@ pub async fn download_and_cache_cover(
     url: &str,
     cache: &CoverCache,
 ) -> Result<Option<String>, String> {
-    let response = reqwest::get(url)
+    download_and_cache_cover_with_headers(url, &[], cache).await
+}
+
+/// Download and cache cover from URL, sending extra request headers
+/// (e.g. `X-Emby-Token` for Emby/Jellyfin image endpoints)
+pub async fn download_and_cache_cover_with_headers(
+    url: &str,
+    headers: &[(String, String)],
+    cache: &CoverCache,
+) -> Result<Option<String>, String> {
+    let mut request = download_client()?.get(url);
+    for (key, value) in headers {
+        request = request.header(key.as_str(), value.as_str());
+    }
+
+    let mut response = request
+        .send()
         .await
-        .map_err(|e| format!("Failed to download: {}", e))?;
+        .map_err(|e| download_error("Failed to download", url, e))?;
 
     if !response.status().is_success() {
         return Ok(None);
     }
+    if response.content_length().is_some_and(|len| len > MAX_DOWNLOAD_BYTES) {
+        return Err(format!("Cover too large (over {} bytes): {}", MAX_DOWNLOAD_BYTES, url));
+    }
 
     let content_type = response
         .headers()
@@ -298,10 +2143,18 @@ pub async fn download_and_cache_cover(
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
 
-    let data = response
-        .bytes()
+    // Read in chunks so a missing or lying Content-Length can't exceed the cap
+    let mut data = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
         .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+        .map_err(|e| download_error("Failed to read response", url, e))?
+    {
+        if data.len() as u64 + chunk.len() as u64 > MAX_DOWNLOAD_BYTES {
+            return Err(format!("Cover too large (over {} bytes): {}", MAX_DOWNLOAD_BYTES, url));
+        }
+        data.extend_from_slice(&chunk);
+    }
 
     if data.is_empty() {
         return Ok(None);
@@ -310,3 +2163,33 @@ pub async fn download_and_cache_cover(
     let hash = cache.save_cover(&data, content_type.as_deref())?;
     Ok(Some(hash))
 }
+
+/// Shared HTTP client for cover downloads, with timeouts, a redirect limit
+/// and a User-Agent (some image hosts reject requests without one).
+/// A client that fails to build fails every download with the same error
+fn download_client() -> Result<&'static reqwest::Client, String> {
+    static CLIENT: OnceLock<Result<reqwest::Client, String>> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            reqwest::Client::builder()
+                .connect_timeout(DOWNLOAD_CONNECT_TIMEOUT)
+                .timeout(DOWNLOAD_TIMEOUT)
+                .redirect(reqwest::redirect::Policy::limited(DOWNLOAD_MAX_REDIRECTS))
+                .user_agent(concat!("BaYin/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .map_err(|e| format!("Failed to build cover download client: {}", e))
+        })
+        .as_ref()
+        .map_err(|e| e.clone())
+}
+
+/// Describe a download error, calling out timeouts explicitly
+fn download_error(context: &str, url: &str, e: reqwest::Error) -> String {
+    if e.is_timeout() {
+        format!("{}: server did not respond in time: {}", context, url)
+    } else if e.is_redirect() {
+        format!("{}: too many redirects: {}", context, url)
+    } else {
+        format!("{}: {}", context, e)
+    }
+}
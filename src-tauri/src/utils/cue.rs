@@ -0,0 +1,226 @@
+//! CUE 索引表：解析单文件整轨专辑的 .cue 文件并拆分为多首歌曲
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use encoding_rs::Encoding;
+
+use crate::models::ScannedSong;
+use crate::utils::mojibake;
+
+/// CUE 中的一条音轨
+#[derive(Debug, Clone, Default)]
+pub struct CueTrack {
+    pub number: u32,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    /// INDEX 01 的开始时间（毫秒）
+    pub start_ms: u64,
+}
+
+/// CUE 中引用的一个音频文件及其音轨
+#[derive(Debug, Clone, Default)]
+pub struct CueFile {
+    pub name: String,
+    pub tracks: Vec<CueTrack>,
+}
+
+/// 解析后的 CUE 索引表
+#[derive(Debug, Clone, Default)]
+pub struct CueSheet {
+    /// 专辑标题
+    pub title: Option<String>,
+    /// 专辑艺术家
+    pub performer: Option<String>,
+    pub files: Vec<CueFile>,
+}
+
+/// 去掉字段值两端的引号
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// 解析 INDEX 时间（mm:ss:ff，每秒 75 帧），返回毫秒
+fn parse_index_time(value: &str) -> Option<u64> {
+    let mut parts = value.trim().split(':');
+    let mins: u64 = parts.next()?.parse().ok()?;
+    let secs: u64 = parts.next()?.parse().ok()?;
+    let frames: u64 = parts.next()?.parse().ok()?;
+    Some(mins * 60_000 + secs * 1000 + frames * 1000 / 75)
+}
+
+/// 解析 CUE 文本
+pub fn parse_cue(text: &str) -> CueSheet {
+    let mut sheet = CueSheet::default();
+
+    for line in text.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match command.to_uppercase().as_str() {
+            "FILE" => {
+                // FILE "name.flac" WAVE：文件名可能含空格，去掉末尾的类型
+                let name = match rest.rsplit_once(char::is_whitespace) {
+                    Some((name, _kind)) if !name.trim().is_empty() => name,
+                    _ => rest,
+                };
+                sheet.files.push(CueFile {
+                    name: unquote(name),
+                    tracks: Vec::new(),
+                });
+            }
+            "TRACK" => {
+                let number = rest
+                    .split_whitespace()
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(0);
+                if let Some(file) = sheet.files.last_mut() {
+                    file.tracks.push(CueTrack {
+                        number,
+                        ..Default::default()
+                    });
+                }
+            }
+            "TITLE" | "PERFORMER" => {
+                let value = Some(unquote(rest)).filter(|v| !v.is_empty());
+                let track = sheet.files.last_mut().and_then(|f| f.tracks.last_mut());
+                match (command.to_uppercase().as_str(), track) {
+                    ("TITLE", Some(track)) => track.title = value,
+                    ("PERFORMER", Some(track)) => track.performer = value,
+                    ("TITLE", None) => sheet.title = value,
+                    _ => sheet.performer = value,
+                }
+            }
+            "INDEX" => {
+                let mut parts = rest.split_whitespace();
+                if parts.next() != Some("01") {
+                    continue;
+                }
+                let start = parts.next().and_then(parse_index_time);
+                let track = sheet.files.last_mut().and_then(|f| f.tracks.last_mut());
+                if let (Some(track), Some(start)) = (track, start) {
+                    track.start_ms = start;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    sheet
+}
+
+/// 查找描述该音频文件的 .cue 文件（同名 .cue 或 "文件名.扩展名.cue"）
+pub fn find_sidecar(audio_path: &Path) -> Option<PathBuf> {
+    let mut candidates = vec![audio_path.with_extension("cue"), audio_path.with_extension("CUE")];
+    if let Some(name) = audio_path.file_name().and_then(|n| n.to_str()) {
+        candidates.push(audio_path.with_file_name(format!("{}.cue", name)));
+    }
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+/// 目录中唯一的 .cue 文件（如 "专辑名.cue" 描述 "CDImage.flac"），有多个时无法确定
+fn single_cue_in_dir(audio_path: &Path) -> Option<PathBuf> {
+    let mut cues = fs::read_dir(audio_path.parent()?)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("cue"))
+                && path.is_file()
+        });
+    let first = cues.next()?;
+    cues.next().is_none().then_some(first)
+}
+
+/// 读取并解析 .cue 文件，编码处理见 `mojibake::decode_text`
+pub fn read_cue(cue_path: &Path, fallback: Option<&'static Encoding>) -> Option<CueSheet> {
+    let data = fs::read(cue_path).ok()?;
+    Some(parse_cue(&mojibake::decode_text(&data, fallback)))
+}
+
+/// CUE 中按文件名（忽略大小写）引用该音频文件的条目
+fn referenced_file<'a>(sheet: &'a CueSheet, file_name: &str) -> Option<&'a CueFile> {
+    sheet.files.iter().find(|f| {
+        Path::new(&f.name)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.eq_ignore_ascii_case(file_name))
+    })
+}
+
+/// 找出 CUE 中引用该音频文件的音轨。同名 CUE 只引用一个文件时直接采用
+/// （整轨抓取后改过扩展名的情况很常见）；目录中的其他 CUE 必须按文件名引用它
+fn tracks_for<'a>(
+    sheet: &'a CueSheet,
+    audio_path: &Path,
+    sidecar: bool,
+) -> Option<&'a [CueTrack]> {
+    let file_name = audio_path.file_name()?.to_str()?;
+    let file = match sheet.files.as_slice() {
+        [only] if sidecar => only,
+        _ => referenced_file(sheet, file_name)?,
+    };
+    Some(file.tracks.as_slice()).filter(|tracks| !tracks.is_empty())
+}
+
+/// 若音频文件有对应的 CUE，将整轨歌曲拆分为多首；否则原样返回。
+/// 先找同名 .cue，没有时采用目录中唯一的、引用了该文件的 .cue
+pub fn split_by_cue(
+    song: ScannedSong,
+    audio_path: &Path,
+    encoding: Option<&'static Encoding>,
+) -> Vec<ScannedSong> {
+    let (sheet, sidecar) = match find_sidecar(audio_path) {
+        Some(path) => (read_cue(&path, encoding), true),
+        None => (single_cue_in_dir(audio_path).and_then(|p| read_cue(&p, encoding)), false),
+    };
+    let Some(sheet) = sheet else {
+        return vec![song];
+    };
+    let Some(tracks) = tracks_for(&sheet, audio_path, sidecar) else {
+        return vec![song];
+    };
+
+    let total_ms = (song.duration * 1000.0) as u64;
+    let track_total = tracks.len() as u32;
+
+    tracks
+        .iter()
+        .enumerate()
+        .map(|(i, track)| {
+            let end_ms = tracks.get(i + 1).map(|next| next.start_ms);
+            let duration_ms = end_ms.unwrap_or(total_ms).saturating_sub(track.start_ms);
+
+            let mut part = song.clone();
+            part.id = format!("{:x}", md5::compute(format!("{}#{}", song.file_path, track.number)));
+            if let Some(title) = &track.title {
+                part.title = title.clone();
+            }
+            if let Some(performer) = track.performer.as_ref().or(sheet.performer.as_ref()) {
+                part.artist = performer.clone();
+                part.artists = vec![performer.clone()];
+            }
+            if let Some(album) = &sheet.title {
+                part.album = album.clone();
+            }
+            if let Some(album_artist) = &sheet.performer {
+                part.album_artists = vec![album_artist.clone()];
+            }
+            part.track_no = Some(track.number);
+            part.track_total = Some(track_total);
+            part.duration = duration_ms as f64 / 1000.0;
+            part.start_ms = Some(track.start_ms);
+            part.end_ms = end_ms;
+            part
+        })
+        .collect()
+}
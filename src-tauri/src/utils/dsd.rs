@@ -0,0 +1,181 @@
+//! 解析 DSD 音频（DSF / DFF）文件头
+//!
+//! lofty 与 symphonia 都不支持 DSD，时长、DSD 采样率等直接从文件头计算。
+//! DSF 末尾的 ID3v2 标签（DFF 中为非标准的 "ID3 " 块）单独取出后交给 lofty 解析。
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// ID3 标签的最大读取长度（含内嵌封面）
+const MAX_ID3_SIZE: u64 = 64 * 1024 * 1024;
+
+/// 一帧静音的 MPEG-1 Layer III 数据（128 kbps / 44.1 kHz，帧长 417 字节）的帧头
+const SILENT_MPEG_HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x00];
+const SILENT_MPEG_FRAME_LEN: usize = 417;
+
+/// 从文件头读取的 DSD 基本信息
+#[derive(Debug, Clone, Default)]
+pub struct DsdInfo {
+    /// DSD 采样率（如 DSD64 = 2822400）
+    pub dsd_rate: u32,
+    pub channels: u8,
+    /// 时长（秒）
+    pub duration: f64,
+    /// 原始 ID3v2 标签数据
+    pub id3_tag: Option<Vec<u8>>,
+}
+
+fn read_at(file: &mut File, offset: u64, buf: &mut [u8]) -> Option<()> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(buf).ok()
+}
+
+/// 读取 ID3 标签：长度超出文件或上限时忽略
+fn read_id3(file: &mut File, offset: u64, len: u64) -> Option<Vec<u8>> {
+    if len == 0 || len > MAX_ID3_SIZE {
+        return None;
+    }
+    let mut tag = vec![0u8; len as usize];
+    read_at(file, offset, &mut tag)?;
+    tag.starts_with(b"ID3").then_some(tag)
+}
+
+fn u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn u64_le(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap_or_default())
+}
+
+fn u64_be(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes[..8].try_into().unwrap_or_default())
+}
+
+/// 解析 DSF（小端）：DSD 块给出元数据偏移，fmt 块给出声道数、采样率和每声道采样数
+fn read_dsf(file: &mut File, file_len: u64) -> Option<DsdInfo> {
+    let mut header = [0u8; 80];
+    read_at(file, 0, &mut header)?;
+    if &header[28..32] != b"fmt " {
+        return None;
+    }
+
+    let metadata_offset = u64_le(&header[20..28]);
+    let channels = u32_le(&header[52..56]);
+    let dsd_rate = u32_le(&header[56..60]);
+    let sample_count = u64_le(&header[64..72]);
+    if dsd_rate == 0 || channels == 0 {
+        return None;
+    }
+
+    let id3_tag = (metadata_offset > 0 && metadata_offset < file_len)
+        .then(|| read_id3(file, metadata_offset, file_len - metadata_offset))
+        .flatten();
+
+    Some(DsdInfo {
+        dsd_rate,
+        channels: channels.min(u8::MAX as u32) as u8,
+        duration: sample_count as f64 / dsd_rate as f64,
+        id3_tag,
+    })
+}
+
+/// 遍历 DSDIFF 块（大端，64 位大小，按 2 字节对齐），返回 (块 ID, 数据偏移, 块大小)
+fn dff_chunks(file: &mut File, start: u64, end: u64) -> Vec<([u8; 4], u64, u64)> {
+    let mut result = Vec::new();
+    let mut pos = start;
+    let mut header = [0u8; 12];
+    while pos + 12 <= end && read_at(file, pos, &mut header).is_some() {
+        let id = [header[0], header[1], header[2], header[3]];
+        let size = u64_be(&header[4..12]);
+        result.push((id, pos + 12, size));
+        pos = pos.saturating_add(12).saturating_add(size).saturating_add(size & 1);
+    }
+    result
+}
+
+/// 解析 DFF：PROP 块给出采样率和声道数；未压缩的 DSD 块按数据大小计算时长，
+/// DST 压缩的按帧数与帧率计算
+fn read_dff(file: &mut File, file_len: u64) -> Option<DsdInfo> {
+    let mut info = DsdInfo::default();
+    let mut sound_bytes = None;
+    let mut dst_frames = None;
+
+    for (id, offset, size) in dff_chunks(file, 16, file_len) {
+        match &id {
+            b"PROP" => {
+                let mut form = [0u8; 4];
+                read_at(file, offset, &mut form)?;
+                if &form != b"SND " {
+                    continue;
+                }
+                for (sub_id, sub_offset, _) in dff_chunks(file, offset + 4, offset + size) {
+                    match &sub_id {
+                        b"FS  " => {
+                            let mut rate = [0u8; 4];
+                            read_at(file, sub_offset, &mut rate)?;
+                            info.dsd_rate = u32::from_be_bytes(rate);
+                        }
+                        b"CHNL" => {
+                            let mut channels = [0u8; 2];
+                            read_at(file, sub_offset, &mut channels)?;
+                            info.channels = u16::from_be_bytes(channels).min(u8::MAX as u16) as u8;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            b"DSD " => sound_bytes = Some(size),
+            b"DST " => {
+                let frte = dff_chunks(file, offset, offset + size)
+                    .into_iter()
+                    .find(|(sub_id, _, _)| sub_id == b"FRTE");
+                if let Some((_, sub_offset, _)) = frte {
+                    let mut frte = [0u8; 6];
+                    read_at(file, sub_offset, &mut frte)?;
+                    let frames = u32::from_be_bytes([frte[0], frte[1], frte[2], frte[3]]);
+                    let rate = u16::from_be_bytes([frte[4], frte[5]]);
+                    dst_frames = Some((frames, rate));
+                }
+            }
+            b"ID3 " => info.id3_tag = read_id3(file, offset, size),
+            _ => {}
+        }
+    }
+
+    if info.dsd_rate == 0 || info.channels == 0 {
+        return None;
+    }
+    info.duration = match (sound_bytes, dst_frames) {
+        (Some(bytes), _) => bytes as f64 * 8.0 / info.channels as f64 / info.dsd_rate as f64,
+        (None, Some((frames, rate))) if rate > 0 => frames as f64 / rate as f64,
+        _ => 0.0,
+    };
+    Some(info)
+}
+
+/// 读取 DSF / DFF 文件头；不是这两种格式时返回 None
+pub fn read_dsd_info(path: &Path) -> Option<DsdInfo> {
+    let mut file = File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+    let mut header = [0u8; 16];
+    file.read_exact(&mut header).ok()?;
+
+    match (&header[..4], &header[12..16]) {
+        (b"DSD ", _) => read_dsf(&mut file, file_len),
+        (b"FRM8", b"DSD ") => read_dff(&mut file, file_len),
+        _ => None,
+    }
+}
+
+/// 将 ID3 标签拼接一帧静音 MPEG 数据，使 lofty 能以 MP3 的方式读取标签
+/// （lofty 不支持 DSD 容器，也没有单独解析 ID3v2 数据的接口）
+pub fn id3_as_mpeg(id3_tag: Option<&[u8]>) -> Vec<u8> {
+    let tag = id3_tag.unwrap_or_default();
+    let mut data = Vec::with_capacity(tag.len() + SILENT_MPEG_FRAME_LEN);
+    data.extend_from_slice(tag);
+    data.extend_from_slice(&SILENT_MPEG_HEADER);
+    data.resize(tag.len() + SILENT_MPEG_FRAME_LEN, 0);
+    data
+}
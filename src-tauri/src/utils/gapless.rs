@@ -0,0 +1,107 @@
+//! 无缝播放信息：编码器延迟与补齐样本数
+//!
+//! 有损编码会在音频前后插入额外的样本（编码器延迟 / 末尾补齐），播放层需要据此裁剪，
+//! 专辑曲目之间才不会出现空隙。来源有两种：
+//! - iTunSMPB 标签（iTunes / Apple 编码的 AAC 和 MP3）
+//! - MP3 首帧中的 LAME（或 FFmpeg 写入的同格式）信息头
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use lofty::tag::{ItemKey, Tag};
+
+/// ID3v2 标签之后最多读取的字节数（首帧及其 Xing/LAME 头一般在此范围内）
+const LAME_SCAN_BYTES: u64 = 8 * 1024;
+
+/// 解析 iTunSMPB 值：" 00000000 00000840 000001CA 0000000000A2B3C6 ..."
+/// 第 2、3 段分别为延迟和补齐（十六进制）
+fn parse_itunsmpb(value: &str) -> Option<(u32, u32)> {
+    let mut fields = value.split_whitespace().skip(1);
+    let delay = u32::from_str_radix(fields.next()?, 16).ok()?;
+    let padding = u32::from_str_radix(fields.next()?, 16).ok()?;
+    Some((delay, padding))
+}
+
+/// 从标签中读取 iTunSMPB（MP4 自由格式原子 / ID3v2 注释 / Vorbis 注释中的键名不同）
+pub fn read_itunsmpb(tag: &Tag) -> Option<(u32, u32)> {
+    tag.items()
+        .filter(|item| match item.key() {
+            ItemKey::Unknown(key) => key.to_lowercase().ends_with("itunsmpb"),
+            _ => false,
+        })
+        .find_map(|item| item.value().text().and_then(parse_itunsmpb))
+}
+
+/// ID3v2 标签的总长度（没有标签时为 0）
+fn id3v2_len(data: &[u8]) -> usize {
+    if data.len() < 10 || &data[..3] != b"ID3" {
+        return 0;
+    }
+    // 同步安全整数：每字节 7 位
+    let size = data[6..10].iter().fold(0usize, |acc, b| (acc << 7) | (*b & 0x7F) as usize);
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    10 + size + footer
+}
+
+/// 从首帧的 Xing/Info 头之后读取 LAME 信息头中的延迟和补齐
+fn parse_lame_header(data: &[u8]) -> Option<(u32, u32)> {
+    let start = id3v2_len(data);
+    let frame = data
+        .get(start..)?
+        .windows(4)
+        .position(|w| w[0] == 0xFF && w[1] & 0xE0 == 0xE0)
+        .map(|pos| start + pos)?;
+    let header = data.get(frame..frame + 4)?;
+
+    // MPEG 版本（3 = MPEG-1）与声道模式（3 = 单声道）决定 side info 长度
+    let mpeg1 = (header[1] >> 3) & 0x03 == 3;
+    let mono = (header[3] >> 6) & 0x03 == 3;
+    let side_info = match (mpeg1, mono) {
+        (true, false) => 32,
+        (true, true) => 17,
+        (false, false) => 17,
+        (false, true) => 9,
+    };
+
+    let xing = frame + 4 + side_info;
+    let tag = data.get(xing..xing + 4)?;
+    if tag != b"Xing" && tag != b"Info" {
+        return None;
+    }
+
+    // 跳过 Xing 头中存在的可选字段（帧数、字节数、TOC、质量）
+    let flags = u32::from_be_bytes(data.get(xing + 4..xing + 8)?.try_into().ok()?);
+    let mut lame = xing + 8;
+    for (bit, len) in [(1, 4), (2, 4), (4, 100), (8, 4)] {
+        if flags & bit != 0 {
+            lame += len;
+        }
+    }
+
+    let encoder = data.get(lame..lame + 4)?;
+    if encoder != b"LAME" && encoder != b"Lavc" && encoder != b"Lavf" {
+        return None;
+    }
+
+    // 编码器字符串(9) 版本(1) 低通(1) 回放增益(8) 编码标志(1) 码率(1) 之后的 3 字节：
+    // 12 位延迟 + 12 位补齐
+    let bytes = data.get(lame + 21..lame + 24)?;
+    let delay = ((bytes[0] as u32) << 4) | ((bytes[1] as u32) >> 4);
+    let padding = (((bytes[1] & 0x0F) as u32) << 8) | bytes[2] as u32;
+    Some((delay, padding))
+}
+
+/// 读取 MP3 文件 LAME 信息头中的编码器延迟和补齐
+/// （不含解码器自身约 529 样本的延迟，由播放层补上）
+pub fn read_lame_gapless(path: &Path) -> Option<(u32, u32)> {
+    let mut file = std::fs::File::open(path).ok()?;
+
+    // 只读标签头，跳过 ID3v2 标签（可能内嵌数 MB 的封面）后读取首帧
+    let mut header = [0u8; 10];
+    file.read_exact(&mut header).ok()?;
+    file.seek(SeekFrom::Start(id3v2_len(&header) as u64)).ok()?;
+
+    let mut data = Vec::new();
+    file.take(LAME_SCAN_BYTES).read_to_end(&mut data).ok()?;
+    parse_lame_header(&data)
+}
@@ -13,16 +13,35 @@ use crate::utils::audio::extract_filename_from_path_str;
 const LOSSLESS_CONTAINERS: &[&str] = &["flac", "wav", "ape", "aiff", "dsf", "dff", "alac"];
 
 /// 构建 Jellyfin/Emby 认证头
+/// - Jellyfin: `Authorization: MediaBrowser ...`（令牌放在 Token 字段）
+/// - Emby: `X-Emby-Authorization: Emby ...`，令牌另附 `X-Emby-Token`
 fn build_auth_header(config: &StreamServerConfig) -> Vec<(String, String)> {
     let mut headers = Vec::new();
-    if let Some(token) = &config.access_token {
-        headers.push(("Authorization".to_string(), format!(
-            "MediaBrowser Client=\"BaYin\", Device=\"BaYin\", DeviceId=\"bayin-app\", Version=\"1.0.0\", Token=\"{}\"",
-            token
-        )));
+    let client_info =
+        "Client=\"BaYin\", Device=\"BaYin\", DeviceId=\"bayin-app\", Version=\"1.0.0\"";
+
+    if config.server_type == ServerType::Emby {
+        let user = config
+            .user_id
+            .as_deref()
+            .map(|id| format!("UserId=\"{}\", ", id))
+            .unwrap_or_default();
+        headers.push((
+            "X-Emby-Authorization".to_string(),
+            format!("Emby {}{}", user, client_info),
+        ));
+        if let Some(token) = &config.access_token {
+            headers.push(("X-Emby-Token".to_string(), token.clone()));
+        }
+    } else if let Some(token) = &config.access_token {
+        headers.push((
+            "Authorization".to_string(),
+            format!("MediaBrowser {}, Token=\"{}\"", client_info, token),
+        ));
     } else {
-        headers.push(("Authorization".to_string(),
-            "MediaBrowser Client=\"BaYin\", Device=\"BaYin\", DeviceId=\"bayin-app\", Version=\"1.0.0\"".to_string()
+        headers.push((
+            "Authorization".to_string(),
+            format!("MediaBrowser {}", client_info),
         ));
     }
     headers
@@ -37,6 +56,7 @@ pub async fn authenticate(config: &StreamServerConfig) -> Result<(String, String
     let client = Client::new();
     let url = format!("{}/Users/AuthenticateByName", base_url(config));
 
+    // 空密码（无密码的本地账户）也要显式发送空字符串的 Pw 字段
     let auth_headers = build_auth_header(config);
     let mut req = client.post(&url).json(&JellyfinAuthRequest {
         username: config.username.clone(),
@@ -188,6 +208,19 @@ fn convert_item(item: &JellyfinItem, config: &StreamServerConfig) -> ScannedSong
         cover_url,
         is_hr: Some(is_hr),
         is_sq: Some(is_sq),
+        // Jellyfin/Emby 的码率单位为 bps
+        bitrate: item
+            .media_sources
+            .as_ref()
+            .and_then(|s| s.first())
+            .and_then(|s| s.bitrate)
+            .map(|b| b / 1000),
+        sample_rate: audio_stream.and_then(|s| s.sample_rate),
+        bit_depth: audio_stream.and_then(|s| s.bit_depth),
+        channels: audio_stream
+            .and_then(|s| s.channels)
+            .and_then(|c| u8::try_from(c).ok()),
+        ..Default::default()
     }
 }
 
@@ -252,6 +285,20 @@ pub async fn fetch_all_songs(config: &StreamServerConfig) -> Result<Vec<ScannedS
     Ok(all_songs)
 }
 
+/// 构建封面图片请求（URL + 认证头），供封面缓存下载使用
+/// `max_width` 让服务器按需缩放，避免下载超大原图
+pub fn cover_request(
+    config: &StreamServerConfig,
+    item_id: &str,
+    max_width: Option<u32>,
+) -> (String, Vec<(String, String)>) {
+    let mut url = format!("{}/Items/{}/Images/Primary", base_url(config), item_id);
+    if let Some(width) = max_width {
+        url.push_str(&format!("?maxWidth={}", width));
+    }
+    (url, build_auth_header(config))
+}
+
 /// 获取流 URL
 pub fn get_stream_url(config: &StreamServerConfig, song_id: &str) -> String {
     let token = config.access_token.as_deref().unwrap_or("");
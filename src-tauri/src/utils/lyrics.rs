@@ -0,0 +1,142 @@
+//! 歌词工具：外部 .lrc 文件查找与 LRC 时间戳解析
+
+use std::path::{Path, PathBuf};
+
+use encoding_rs::Encoding;
+use serde::Serialize;
+
+use crate::utils::mojibake;
+
+/// 解析后的一行歌词
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LyricLine {
+    /// 开始时间（毫秒）
+    pub time_ms: u64,
+    pub text: String,
+    /// 是否为 [ti:] / [ar:] 等元数据标签（time_ms 为 0）
+    pub is_metadata: bool,
+}
+
+/// 查找与音频文件同目录、同名的 .lrc 歌词文件
+pub fn find_sidecar(audio_path: &Path) -> Option<PathBuf> {
+    ["lrc", "LRC"]
+        .iter()
+        .map(|ext| audio_path.with_extension(ext))
+        .find(|p| p.is_file())
+}
+
+/// 读取同名 .lrc 歌词文件，编码处理见 `mojibake::decode_text`
+pub fn read_sidecar(audio_path: &Path, fallback: Option<&'static Encoding>) -> Option<String> {
+    let data = std::fs::read(find_sidecar(audio_path)?).ok()?;
+    Some(mojibake::decode_text(&data, fallback))
+}
+
+/// 解析 LRC 时间标签内容（如 "01:23.45" / "01:23.456" / "01:23"），返回毫秒
+fn parse_timestamp(tag: &str) -> Option<u64> {
+    let (mins, rest) = tag.split_once(':')?;
+    let (secs, frac) = rest.split_once(['.', ':']).unwrap_or((rest, ""));
+
+    if mins.is_empty() || !mins.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if secs.is_empty() || !secs.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let mins: u64 = mins.parse().ok()?;
+    let secs: u64 = secs.parse().ok()?;
+    // 小数部分按位数换算为毫秒：.4 -> 400，.45 -> 450，.456 -> 456
+    let frac_ms = if frac.is_empty() {
+        0
+    } else {
+        let digits = &frac[..frac.len().min(3)];
+        digits.parse::<u64>().ok()? * 10u64.pow(3 - digits.len() as u32)
+    };
+
+    Some(mins * 60_000 + secs * 1000 + frac_ms)
+}
+
+/// 拆分一行开头的所有 [..] 标签，返回（标签列表，剩余文本）
+fn split_tags(line: &str) -> (Vec<&str>, &str) {
+    let mut tags = Vec::new();
+    let mut rest = line.trim();
+
+    while let Some(inner) = rest.strip_prefix('[') {
+        match inner.find(']') {
+            Some(end) => {
+                tags.push(&inner[..end]);
+                rest = inner[end + 1..].trim_start();
+            }
+            None => break,
+        }
+    }
+
+    (tags, rest.trim_end())
+}
+
+/// 判断歌词是否为带时间戳的同步歌词（LRC 格式）
+pub fn is_synced(text: &str) -> bool {
+    text.lines()
+        .any(|line| split_tags(line).0.iter().any(|t| parse_timestamp(t).is_some()))
+}
+
+/// 解析歌词为结构化的行：元数据标签在前，其余按时间排序
+/// - 一行多个时间戳会展开为多行
+/// - 没有时间戳的文本行沿用上一行的时间
+/// - [offset:] 标签会应用到所有时间上（正值表示歌词整体提前）
+pub fn parse_lyric_lines(text: &str) -> Vec<LyricLine> {
+    let mut offset_ms: i64 = 0;
+    let mut metadata: Vec<LyricLine> = Vec::new();
+    let mut lines: Vec<LyricLine> = Vec::new();
+    let mut prev_ms: u64 = 0;
+
+    for line in text.lines() {
+        let (tags, content) = split_tags(line);
+        let mut first_ms: Option<u64> = None;
+
+        for tag in &tags {
+            if let Some(ms) = parse_timestamp(tag) {
+                first_ms.get_or_insert(ms);
+                lines.push(LyricLine {
+                    time_ms: ms,
+                    text: content.to_string(),
+                    is_metadata: false,
+                });
+            } else if let Some((key, value)) = tag.split_once(':') {
+                let key = key.trim();
+                if key.eq_ignore_ascii_case("offset") {
+                    offset_ms = value.trim().parse().unwrap_or(0);
+                } else if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphabetic()) {
+                    metadata.push(LyricLine {
+                        time_ms: 0,
+                        text: format!("{}:{}", key, value.trim()),
+                        is_metadata: true,
+                    });
+                }
+            }
+        }
+
+        match first_ms {
+            Some(ms) => prev_ms = ms,
+            None if !content.is_empty() => lines.push(LyricLine {
+                time_ms: prev_ms,
+                text: content.to_string(),
+                is_metadata: false,
+            }),
+            None => {}
+        }
+    }
+
+    for line in &mut lines {
+        line.time_ms = (line.time_ms as i64 - offset_ms).max(0) as u64;
+    }
+    // 稳定排序，保证同一时间的行保持原有顺序
+    lines.sort_by_key(|line| line.time_ms);
+
+    metadata.extend(lines);
+    metadata
+}
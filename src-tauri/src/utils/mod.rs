@@ -2,3 +2,22 @@ pub mod audio;
 pub mod jellyfin;
 pub mod subsonic;
 pub mod cover;
+pub mod lyrics;
+pub mod tags;
+pub mod cue;
+pub mod blurhash;
+pub mod palette;
+pub mod sort;
+pub mod playlist;
+pub mod search;
+pub mod albums;
+pub mod gapless;
+pub mod archive;
+pub mod chapters;
+pub mod pcm;
+pub mod musical_key;
+pub mod mojibake;
+pub mod analysis;
+pub mod cdtoc;
+pub mod waveform;
+pub mod dsd;
@@ -0,0 +1,112 @@
+//! 乱码标签修复
+//!
+//! 旧的 MP3 常把 GBK / Shift-JIS / Big5 编码的文字直接写进 ID3v1 或 Latin-1 编码的
+//! ID3v2 帧，读出来就成了 "ÖÜ¹úÈË" 之类的乱码。把这类字符串还原成原始字节，
+//! 再按用户指定的本地编码重新解码，得到有效的中日韩文字时才替换。
+
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+use crate::models::ScannedSong;
+
+/// 按名称查找编码（"gbk"、"gb18030"、"shift_jis"、"big5" 等 WHATWG 标签）
+pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+/// 解码外部文本文件（.cue、.lrc）：有 BOM 时按 BOM（UTF-8 / UTF-16），否则优先 UTF-8；
+/// 不是有效 UTF-8 时按 `fallback`（扫描选项中的本地编码，如 GBK、Big5）解码，
+/// 未指定时按 Windows-1252
+pub fn decode_text(data: &[u8], fallback: Option<&'static Encoding>) -> String {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(data) {
+        return encoding.decode_without_bom_handling(&data[bom_len..]).0.into_owned();
+    }
+    match std::str::from_utf8(data) {
+        Ok(text) => text.to_string(),
+        Err(_) => fallback
+            .unwrap_or(WINDOWS_1252)
+            .decode_without_bom_handling(data)
+            .0
+            .into_owned(),
+    }
+}
+
+/// 把按 Latin-1（或 Windows-1252）误解码的字符串还原为原始字节
+/// 含有这两种编码之外的字符时说明不是乱码，返回 None
+fn latin1_bytes(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut buf = [0u8; 4];
+    for c in value.chars() {
+        if (c as u32) < 0x100 {
+            bytes.push(c as u8);
+            continue;
+        }
+        // "œ"、"€" 等 Windows-1252 在 0x80-0x9F 区间的字符
+        let (encoded, _, had_errors) = WINDOWS_1252.encode(c.encode_utf8(&mut buf));
+        match encoded.as_ref() {
+            [byte] if !had_errors => bytes.push(*byte),
+            _ => return None,
+        }
+    }
+    Some(bytes)
+}
+
+/// 高位字节（>= 0x80）是否都成对出现：双字节编码的乱码里它们总是连续两个，
+/// 而 "Müller"、"Café" 这样的正常西文只有零散的单个
+fn high_bytes_paired(bytes: &[u8]) -> bool {
+    bytes
+        .split(|b| *b < 0x80)
+        .all(|run| run.len() % 2 == 0)
+}
+
+/// 是否为中日韩文字（汉字、假名、谚文）或全角标点
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3000..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xFF00..=0xFFEF
+    )
+}
+
+/// 尝试修复单个字符串，无需修复或无法修复时返回 None
+pub fn repair(value: &str, encoding: &'static Encoding) -> Option<String> {
+    if value.is_ascii() {
+        return None;
+    }
+    let bytes = latin1_bytes(value)?;
+    if !high_bytes_paired(&bytes) {
+        return None;
+    }
+
+    let decoded = encoding.decode_without_bom_handling_and_without_replacement(&bytes)?;
+    let valid = decoded.chars().any(is_cjk) && !decoded.chars().any(char::is_control);
+    valid.then(|| decoded.into_owned())
+}
+
+/// 修复歌曲的文本字段，有字段被修复时返回 true
+pub fn repair_song(song: &mut ScannedSong, encoding: &'static Encoding) -> bool {
+    let mut repaired = false;
+    let mut fix = |value: &mut String| {
+        if let Some(fixed) = repair(value, encoding) {
+            *value = fixed;
+            repaired = true;
+        }
+    };
+
+    fix(&mut song.title);
+    fix(&mut song.artist);
+    fix(&mut song.album);
+    song.artists.iter_mut().for_each(&mut fix);
+    song.album_artists.iter_mut().for_each(&mut fix);
+    song.genres.iter_mut().for_each(&mut fix);
+    song.composers.iter_mut().for_each(&mut fix);
+    for value in [
+        &mut song.artist_sort,
+        &mut song.album_artist_sort,
+        &mut song.album_sort,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        fix(value);
+    }
+    repaired
+}
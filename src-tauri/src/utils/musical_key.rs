@@ -0,0 +1,73 @@
+//! 调性标签（INITIALKEY / TKEY）的解析与统一
+//!
+//! DJ 软件写入的调性格式各不相同：标准写法（"Am"、"F# minor"、"Bbmaj"）、
+//! Camelot 轮盘（"8A"）和 Traktor 的 Open Key（"1m"）。统一转换为标准写法，
+//! 大调只写根音，小调加 "m"，变化音按常用写法选择升号或降号。
+
+/// 按音级（C = 0）排列的大调名称
+const MAJOR_KEYS: [&str; 12] = ["C", "Db", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B"];
+
+/// 按音级（C = 0）排列的小调名称
+const MINOR_KEYS: [&str; 12] = [
+    "Cm", "C#m", "Dm", "Ebm", "Em", "Fm", "F#m", "Gm", "G#m", "Am", "Bbm", "Bm",
+];
+
+/// 将调性标签统一为标准写法，无法识别（如 Traktor 的 "o" 表示无调性）时返回 None
+pub fn normalize_key(value: &str) -> Option<String> {
+    let value = value.trim();
+    let (pitch, minor) = parse_wheel(value).or_else(|| parse_standard(value))?;
+    let names = if minor { &MINOR_KEYS } else { &MAJOR_KEYS };
+    Some(names[pitch].to_string())
+}
+
+/// 解析 Camelot（"8A" / "8B"）或 Open Key（"1m" / "1d"），返回 (音级, 是否小调)
+fn parse_wheel(value: &str) -> Option<(usize, bool)> {
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (number, mode) = value.split_at(split);
+    let number: usize = number.parse().ok().filter(|n| (1..=12).contains(n))?;
+
+    // 换算为 Camelot 编号：Open Key 的 1d/1m 对应 Camelot 的 8B/8A
+    let (camelot, minor) = match mode.to_ascii_lowercase().as_str() {
+        "a" => (number, true),
+        "b" => (number, false),
+        "m" => ((number + 6) % 12 + 1, true),
+        "d" => ((number + 6) % 12 + 1, false),
+        _ => return None,
+    };
+
+    // Camelot 每进一格为纯五度（7 个半音）；8B = C 大调，8A = A 小调
+    let steps = (camelot + 12 - 8) % 12;
+    let root = if minor { 9 } else { 0 };
+    Some(((root + steps * 7) % 12, minor))
+}
+
+/// 解析标准写法：根音 + 可选升降号 + 可选调式（"m"、"min"、"minor"、"maj"、"major"）
+fn parse_standard(value: &str) -> Option<(usize, bool)> {
+    let mut chars = value.chars();
+    let natural = match chars.next()?.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+
+    let rest = chars.as_str();
+    let (pitch, rest) = if let Some(rest) = rest.strip_prefix(['#', '♯']) {
+        (natural + 1, rest)
+    } else if let Some(rest) = rest.strip_prefix(['b', '♭']) {
+        (natural + 11, rest)
+    } else {
+        (natural, rest)
+    };
+
+    let minor = match rest.trim().to_lowercase().as_str() {
+        "" | "maj" | "major" => false,
+        "m" | "min" | "minor" | "-" => true,
+        _ => return None,
+    };
+    Some((pitch % 12, minor))
+}
@@ -0,0 +1,81 @@
+//! Dominant color / palette extraction for cover tinting
+//!
+//! Uses a coarse color histogram: pixels are bucketed by their top 4 bits
+//! per channel, and the most populated buckets (averaged) become the palette.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use image::RgbImage;
+
+/// Minimum squared RGB distance between two palette colors
+const MIN_COLOR_DISTANCE_SQ: u32 = 48 * 48;
+
+/// Treat near-black and near-white pixels as background (borders, letterboxing)
+fn is_background(pixel: [u8; 3]) -> bool {
+    let max = *pixel.iter().max().unwrap_or(&0);
+    let min = *pixel.iter().min().unwrap_or(&0);
+    max < 24 || min > 232
+}
+
+fn distance_sq(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x as i32 - *y as i32).pow(2) as u32)
+        .sum()
+}
+
+/// Build a histogram of (count, summed channels) per 4-bit bucket
+fn histogram(img: &RgbImage, skip_background: bool) -> HashMap<u16, (u32, [u32; 3])> {
+    let mut buckets: HashMap<u16, (u32, [u32; 3])> = HashMap::new();
+    for pixel in img.pixels() {
+        let rgb = pixel.0;
+        if skip_background && is_background(rgb) {
+            continue;
+        }
+        let key = ((rgb[0] as u16 >> 4) << 8) | ((rgb[1] as u16 >> 4) << 4) | (rgb[2] as u16 >> 4);
+        let bucket = buckets.entry(key).or_insert((0, [0; 3]));
+        bucket.0 += 1;
+        for (sum, channel) in bucket.1.iter_mut().zip(rgb) {
+            *sum += channel as u32;
+        }
+    }
+    buckets
+}
+
+/// Extract up to `count` prominent colors, most prominent first.
+/// Background-like pixels are ignored unless the image has nothing else
+pub fn extract(img: &RgbImage, count: usize) -> Vec<[u8; 3]> {
+    let mut buckets = histogram(img, true);
+    if buckets.is_empty() {
+        buckets = histogram(img, false);
+    }
+
+    let mut ranked: Vec<(u32, [u8; 3])> = buckets
+        .into_values()
+        .map(|(n, sum)| {
+            let avg = [
+                (sum[0] / n) as u8,
+                (sum[1] / n) as u8,
+                (sum[2] / n) as u8,
+            ];
+            (n, avg)
+        })
+        .collect();
+    ranked.sort_by_key(|(n, _)| Reverse(*n));
+
+    // Greedily pick distinct colors so the palette isn't five shades of one hue
+    let mut palette: Vec<[u8; 3]> = Vec::with_capacity(count);
+    for (_, color) in ranked {
+        if palette.len() >= count {
+            break;
+        }
+        if palette
+            .iter()
+            .all(|picked| distance_sq(*picked, color) >= MIN_COLOR_DISTANCE_SQ)
+        {
+            palette.push(color);
+        }
+    }
+    palette
+}
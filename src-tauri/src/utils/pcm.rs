@@ -0,0 +1,124 @@
+//! 直接解析 WAV / AIFF 文件头，用于 lofty 无法解析的文件（如录音机写入的非标准块）
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 从文件头读取的 PCM 基本信息
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PcmInfo {
+    /// 时长（秒），无法计算时为 None
+    pub duration: Option<f64>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u8>,
+    pub bit_depth: Option<u8>,
+}
+
+/// 依次遍历 RIFF / IFF 块，返回 (块 ID, 块数据偏移, 块大小)
+/// WAV 为小端，AIFF 为大端；块按 2 字节对齐
+fn chunks(file: &mut File, big_endian: bool) -> Vec<([u8; 4], u64, u64)> {
+    let mut result = Vec::new();
+    let mut pos = 12u64;
+    let mut header = [0u8; 8];
+    while file.seek(SeekFrom::Start(pos)).is_ok() && file.read_exact(&mut header).is_ok() {
+        let id = [header[0], header[1], header[2], header[3]];
+        let size_bytes = [header[4], header[5], header[6], header[7]];
+        let size = if big_endian {
+            u32::from_be_bytes(size_bytes)
+        } else {
+            u32::from_le_bytes(size_bytes)
+        } as u64;
+        result.push((id, pos + 8, size));
+        pos += 8 + size + (size & 1);
+    }
+    result
+}
+
+fn read_at(file: &mut File, offset: u64, buf: &mut [u8]) -> Option<()> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(buf).ok()
+}
+
+/// 解析 WAV：fmt 块给出格式，data 块大小除以每秒字节数得到时长
+/// （流式写入的录音文件 data 大小可能为 0 或 0xFFFFFFFF，此时以文件末尾为准）
+fn read_wav(file: &mut File, file_len: u64) -> Option<PcmInfo> {
+    let mut info = PcmInfo::default();
+    let mut byte_rate = 0u32;
+
+    for (id, offset, size) in chunks(file, false) {
+        match &id {
+            b"fmt " => {
+                let mut fmt = [0u8; 16];
+                read_at(file, offset, &mut fmt)?;
+                info.channels = Some(u16::from_le_bytes([fmt[2], fmt[3]]) as u8);
+                info.sample_rate = Some(u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]));
+                byte_rate = u32::from_le_bytes([fmt[8], fmt[9], fmt[10], fmt[11]]);
+                info.bit_depth = Some(u16::from_le_bytes([fmt[14], fmt[15]]) as u8);
+            }
+            b"data" => {
+                let truncated = offset + size > file_len;
+                let data_len = if size == 0 || size == u32::MAX as u64 || truncated {
+                    file_len.saturating_sub(offset)
+                } else {
+                    size
+                };
+                if byte_rate > 0 {
+                    info.duration = Some(data_len as f64 / byte_rate as f64);
+                }
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+/// 80 位 IEEE 扩展精度浮点数（AIFF 的采样率字段）
+fn extended_to_f64(bytes: &[u8; 10]) -> f64 {
+    let exponent = (((bytes[0] & 0x7F) as i32) << 8) | bytes[1] as i32;
+    let mantissa = u64::from_be_bytes([
+        bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7], bytes[8], bytes[9],
+    ]);
+    if exponent == 0 && mantissa == 0 {
+        return 0.0;
+    }
+    let value = mantissa as f64 * 2f64.powi(exponent - 16383 - 63);
+    if bytes[0] & 0x80 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// 解析 AIFF：COMM 块给出声道数、采样帧数、位深和采样率
+fn read_aiff(file: &mut File) -> Option<PcmInfo> {
+    let (_, offset, _) = chunks(file, true).into_iter().find(|(id, _, _)| id == b"COMM")?;
+    let mut comm = [0u8; 18];
+    read_at(file, offset, &mut comm)?;
+
+    let frames = u32::from_be_bytes([comm[2], comm[3], comm[4], comm[5]]);
+    let rate_bytes: [u8; 10] = comm[8..18].try_into().ok()?;
+    let sample_rate = extended_to_f64(&rate_bytes);
+
+    Some(PcmInfo {
+        duration: (sample_rate > 0.0).then(|| frames as f64 / sample_rate),
+        sample_rate: Some(sample_rate.round() as u32),
+        channels: Some(u16::from_be_bytes([comm[0], comm[1]]) as u8),
+        bit_depth: Some(u16::from_be_bytes([comm[6], comm[7]]) as u8),
+    })
+}
+
+/// 读取 WAV / AIFF 文件头；不是这两种格式时返回 None
+pub fn read_pcm_info(path: &Path) -> Option<PcmInfo> {
+    let mut file = File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+    let mut header = [0u8; 12];
+    file.read_exact(&mut header).ok()?;
+
+    match (&header[..4], &header[8..12]) {
+        (b"RIFF", b"WAVE") | (b"RF64", b"WAVE") => read_wav(&mut file, file_len),
+        (b"FORM", b"AIFF") | (b"FORM", b"AIFC") => read_aiff(&mut file),
+        _ => None,
+    }
+}
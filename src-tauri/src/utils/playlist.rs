@@ -0,0 +1,185 @@
+//! 播放列表文件的读写：导出 M3U / M3U8，导入 M3U / M3U8 / PLS
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::models::PlaylistFormat;
+use crate::utils::audio::resolve_path;
+
+/// UTF-8 BOM（部分 Windows 播放器据此识别 M3U8 编码）
+const UTF8_BOM: &str = "\u{feff}";
+
+/// 播放列表中的一条音轨
+#[derive(Debug, Clone, Default)]
+pub struct PlaylistEntry {
+    pub path: PathBuf,
+    /// 时长（秒），未知时为 None
+    pub duration: Option<f64>,
+    /// 显示名称（"艺术家 - 标题"）
+    pub title: Option<String>,
+}
+
+/// 计算 target 相对于 base 目录的路径；两者除根目录（盘符）外没有共同祖先时返回 None
+fn relative_path(target: &Path, base: &Path) -> Option<PathBuf> {
+    let target: Vec<Component> = target.components().collect();
+    let base: Vec<Component> = base.components().collect();
+
+    let common = target.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    if !target[..common].iter().any(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+    Some(relative)
+}
+
+/// 生成扩展 M3U 文本；与播放列表文件有共同祖先目录的音轨写入相对路径，否则写入绝对路径
+pub fn format_m3u(entries: &[PlaylistEntry], base_dir: &Path) -> String {
+    let mut out = String::from("#EXTM3U\n");
+
+    for entry in entries {
+        // 时长未知时按惯例写 -1
+        let seconds = entry.duration.map(|d| d.round() as i64).unwrap_or(-1);
+        let title = entry.title.clone().unwrap_or_else(|| {
+            entry
+                .path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        out.push_str(&format!("#EXTINF:{},{}\n", seconds, title));
+
+        let path = relative_path(&entry.path, base_dir).unwrap_or_else(|| entry.path.clone());
+        out.push_str(&path.to_string_lossy());
+        out.push('\n');
+    }
+
+    out
+}
+
+/// 写出播放列表文件（M3U8 带 UTF-8 BOM，M3U 不带）
+pub fn write_playlist(
+    entries: &[PlaylistEntry],
+    out_path: &Path,
+    format: PlaylistFormat,
+) -> Result<(), String> {
+    let base_dir = out_path.parent().unwrap_or_else(|| Path::new(""));
+    let body = format_m3u(entries, base_dir);
+
+    let text = match format {
+        PlaylistFormat::M3u8 => format!("{}{}", UTF8_BOM, body),
+        PlaylistFormat::M3u => body,
+    };
+
+    std::fs::write(out_path, text).map_err(|e| format!("无法写入播放列表: {}", e))
+}
+
+/// 解码播放列表文本：优先 UTF-8（去掉 BOM），否则按 Latin-1 处理（旧式 .m3u 常见）
+fn decode_text(data: &[u8]) -> String {
+    match std::str::from_utf8(data) {
+        Ok(text) => text.trim_start_matches(UTF8_BOM).to_string(),
+        Err(_) => data.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// 解析 M3U / M3U8：#EXTINF 的时长和标题附加到其后的第一条路径
+fn parse_m3u(text: &str) -> Vec<(String, Option<f64>, Option<String>)> {
+    let mut items = Vec::new();
+    let mut pending: (Option<f64>, Option<String>) = (None, None);
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            let (duration, title) = info.split_once(',').unwrap_or((info, ""));
+            // 时长后可能跟有属性（tvg-id="..." 等），只取数字部分
+            let duration = duration.split_whitespace().next().and_then(|d| d.parse().ok());
+            pending = (
+                duration.filter(|d: &f64| *d >= 0.0),
+                Some(title.trim().to_string()).filter(|t| !t.is_empty()),
+            );
+        } else if !line.is_empty() && !line.starts_with('#') {
+            let (duration, title) = std::mem::take(&mut pending);
+            items.push((line.to_string(), duration, title));
+        }
+    }
+
+    items
+}
+
+/// PLS 条目的 (路径, 时长, 标题)，路径缺失的条目会被丢弃
+type PlsEntry = (Option<String>, Option<f64>, Option<String>);
+
+/// 解析 PLS：按 FileN / TitleN / LengthN 的编号组合条目
+fn parse_pls(text: &str) -> Vec<(String, Option<f64>, Option<String>)> {
+    let mut items: BTreeMap<u32, PlsEntry> = BTreeMap::new();
+
+    for line in text.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim().to_string();
+        let field = ["file", "title", "length"]
+            .into_iter()
+            .find_map(|name| Some((name, key.strip_prefix(name)?.parse::<u32>().ok()?)));
+        let Some((name, index)) = field else {
+            continue;
+        };
+
+        let item = items.entry(index).or_default();
+        match name {
+            "file" => item.0 = Some(value),
+            "title" => item.2 = Some(value).filter(|v| !v.is_empty()),
+            _ => item.1 = value.parse().ok().filter(|d: &f64| *d >= 0.0),
+        }
+    }
+
+    items
+        .into_values()
+        .filter_map(|(file, duration, title)| Some((file?, duration, title)))
+        .collect()
+}
+
+/// 将播放列表中的路径解析为绝对路径：相对路径相对于播放列表所在目录
+fn resolve_location(location: &str, base_dir: &Path) -> PathBuf {
+    // 其他系统生成的播放列表可能使用反斜杠分隔
+    let location = if cfg!(windows) {
+        location.to_string()
+    } else {
+        location.replace('\\', "/")
+    };
+    let path = resolve_path(&location);
+    if path.is_absolute() {
+        path
+    } else {
+        base_dir.join(path)
+    }
+}
+
+/// 读取播放列表文件（按扩展名区分 PLS 与 M3U），返回其中的音轨（不检查文件是否存在）
+pub fn read_playlist(playlist_path: &Path) -> Result<Vec<PlaylistEntry>, String> {
+    let data = std::fs::read(playlist_path).map_err(|e| format!("无法读取播放列表: {}", e))?;
+    let text = decode_text(&data);
+
+    let is_pls = playlist_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("pls"));
+    let items = if is_pls { parse_pls(&text) } else { parse_m3u(&text) };
+
+    let base_dir = playlist_path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(items
+        .into_iter()
+        .map(|(location, duration, title)| PlaylistEntry {
+            path: resolve_location(&location, base_dir),
+            duration,
+            title,
+        })
+        .collect())
+}
@@ -0,0 +1,66 @@
+//! 歌曲搜索：Unicode 规范化后忽略大小写和变音符号的子串匹配，
+//! 并支持用拼音或拼音首字母匹配中文（"zjl" 匹配 "周杰伦"）
+
+use rayon::prelude::*;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::models::ScannedSong;
+use crate::utils::sort::{pinyin_initials, pinyin_sort_key};
+
+/// 规范化文本用于比较：NFKD 分解（全角转半角等）、去掉变音符号、转小写
+pub fn fold(text: &str) -> String {
+    text.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 拼音匹配：完整拼音（去掉空格）或拼音首字母包含查询串
+fn pinyin_matches(field: &str, query: &str) -> bool {
+    // 没有汉字的字段不需要再按拼音匹配
+    if field.is_ascii() {
+        return false;
+    }
+    let full: String = pinyin_sort_key(field).split_whitespace().collect();
+    full.to_lowercase().contains(query) || pinyin_initials(field).contains(query)
+}
+
+/// 单首歌曲的匹配排名（越小越靠前）：标题 > 艺术家 > 专辑，同一字段内前缀匹配优先；
+/// 拼音匹配排在所有直接匹配之后
+fn match_rank(song: &ScannedSong, query: &str, pinyin_query: bool) -> Option<u32> {
+    let fields = [&song.title, &song.artist, &song.album];
+    let direct = fields.iter().enumerate().find_map(|(priority, field)| {
+        let folded = fold(field);
+        let pos = folded.find(query)?;
+        Some(priority as u32 * 2 + u32::from(pos != 0))
+    });
+    if direct.is_some() || !pinyin_query {
+        return direct;
+    }
+
+    let base = fields.len() as u32 * 2;
+    fields
+        .iter()
+        .position(|field| pinyin_matches(field, query))
+        .map(|priority| base + priority as u32)
+}
+
+/// 在歌曲列表中搜索，返回匹配歌曲的下标（按排名排序，同排名保持原顺序）
+/// 查询为空时返回全部下标
+pub fn search(songs: &[ScannedSong], query: &str) -> Vec<usize> {
+    let query = fold(query.trim());
+    if query.is_empty() {
+        return (0..songs.len()).collect();
+    }
+    // 只有纯字母数字的查询才可能是拼音
+    let pinyin_query = query.chars().all(|c| c.is_ascii_alphanumeric());
+
+    let mut matches: Vec<(u32, usize)> = songs
+        .par_iter()
+        .enumerate()
+        .filter_map(|(i, song)| match_rank(song, &query, pinyin_query).map(|rank| (rank, i)))
+        .collect();
+    matches.sort_unstable();
+    matches.into_iter().map(|(_, i)| i).collect()
+}
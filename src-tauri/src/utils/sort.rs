@@ -0,0 +1,113 @@
+//! 自然排序：按数值比较名称中嵌入的数字（"Disc 2" 排在 "Disc 10" 之前），
+//! 以及按拼音排序中文名称
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use pinyin::ToPinyin;
+
+use crate::models::ScannedSong;
+
+/// 取出连续的数字串
+fn take_digits(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(c);
+    }
+    digits
+}
+
+/// 按数值比较两个数字串（不受长度限制）；数值相同时前导零少的在前
+fn cmp_digits(a: &str, b: &str) -> Ordering {
+    let a_trimmed = a.trim_start_matches('0');
+    let b_trimmed = b.trim_start_matches('0');
+    a_trimmed
+        .len()
+        .cmp(&b_trimmed.len())
+        .then_with(|| a_trimmed.cmp(b_trimmed))
+        .then_with(|| a.len().cmp(&b.len()))
+}
+
+/// 自然顺序比较（忽略大小写，Unicode 感知的小写转换）
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let a = a.to_lowercase();
+    let b = b.to_lowercase();
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let ordering = cmp_digits(&take_digits(&mut a_chars), &take_digits(&mut b_chars));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.cmp(y);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+/// 拼音首字母：汉字取拼音首字母，其他字母和数字转小写保留，其余字符忽略
+/// （"周杰伦" -> "zjl"）
+pub fn pinyin_initials(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c.to_pinyin() {
+            Some(py) => py.first_letter().chars().next(),
+            None if c.is_alphanumeric() => Some(c),
+            None => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 排序用的拼音键：汉字替换为不带声调的拼音，其余字符保持不变
+/// （"周杰伦" -> "zhou jie lun "，与 "Zhang" 等拉丁字母名称按字母顺序混排）
+pub fn pinyin_sort_key(text: &str) -> String {
+    let mut key = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c.to_pinyin() {
+            Some(py) => {
+                key.push_str(py.plain());
+                key.push(' ');
+            }
+            None => key.push(c),
+        }
+    }
+    key
+}
+
+/// 按拼音的自然顺序比较
+pub fn pinyin_cmp(a: &str, b: &str) -> Ordering {
+    natural_cmp(&pinyin_sort_key(a), &pinyin_sort_key(b))
+}
+
+/// 索引栏字母：拼音（或拉丁字母）首字母的大写，数字和其他字符归入 '#'
+pub fn index_letter(text: &str) -> char {
+    pinyin_initials(text)
+        .chars()
+        .next()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .unwrap_or('#')
+}
+
+/// 专辑内歌曲顺序：碟号、音轨号（缺失的排在后面），再按标题自然排序
+pub fn song_cmp(a: &ScannedSong, b: &ScannedSong) -> Ordering {
+    let key = |n: Option<u32>| n.unwrap_or(u32::MAX);
+    key(a.disc_no)
+        .cmp(&key(b.disc_no))
+        .then_with(|| key(a.track_no).cmp(&key(b.track_no)))
+        .then_with(|| natural_cmp(&a.title, &b.title))
+}
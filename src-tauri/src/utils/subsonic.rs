@@ -138,6 +138,10 @@ fn convert_song(song: &SubsonicSong, config: &StreamServerConfig) -> ScannedSong
         cover_url,
         is_hr: Some(is_hr),
         is_sq: Some(is_sq),
+        bitrate: song.bit_rate,
+        sample_rate: song.sampling_rate,
+        bit_depth: song.bit_depth,
+        ..Default::default()
     }
 }
 
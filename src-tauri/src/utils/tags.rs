@@ -0,0 +1,190 @@
+//! 标签读写工具函数（按键名读取任意标签、写回编辑后的标签）
+
+use std::fs;
+use std::path::Path;
+
+use lofty::config::WriteOptions;
+use lofty::file::TaggedFile;
+use lofty::picture::{MimeType, Picture, PictureType};
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::{ItemKey, Tag};
+
+use crate::models::TagChanges;
+
+/// 常用标签名（Vorbis 风格，大写）到 lofty ItemKey 的映射
+fn well_known_key(key: &str) -> Option<ItemKey> {
+    let item_key = match key {
+        "TITLE" => ItemKey::TrackTitle,
+        "ARTIST" => ItemKey::TrackArtist,
+        "ALBUM" => ItemKey::AlbumTitle,
+        "ALBUMARTIST" | "ALBUM_ARTIST" | "ALBUM ARTIST" => ItemKey::AlbumArtist,
+        "GENRE" => ItemKey::Genre,
+        "COMPOSER" => ItemKey::Composer,
+        "DATE" => ItemKey::RecordingDate,
+        "YEAR" => ItemKey::Year,
+        "TRACKNUMBER" | "TRACK" => ItemKey::TrackNumber,
+        "TRACKTOTAL" | "TOTALTRACKS" => ItemKey::TrackTotal,
+        "DISCNUMBER" | "DISC" => ItemKey::DiscNumber,
+        "DISCTOTAL" | "TOTALDISCS" => ItemKey::DiscTotal,
+        "COMMENT" => ItemKey::Comment,
+        "LYRICS" => ItemKey::Lyrics,
+        "ISRC" => ItemKey::Isrc,
+        "BPM" => ItemKey::Bpm,
+        "LABEL" => ItemKey::Label,
+        "COPYRIGHT" => ItemKey::CopyrightMessage,
+        "MUSICBRAINZ_ALBUMID" => ItemKey::MusicBrainzReleaseId,
+        "MUSICBRAINZ_TRACKID" => ItemKey::MusicBrainzRecordingId,
+        "MUSICBRAINZ_RELEASETRACKID" => ItemKey::MusicBrainzTrackId,
+        "MUSICBRAINZ_ARTISTID" => ItemKey::MusicBrainzArtistId,
+        "MUSICBRAINZ_ALBUMARTISTID" => ItemKey::MusicBrainzReleaseArtistId,
+        _ => return None,
+    };
+    Some(item_key)
+}
+
+/// 按原始键名查找（TXXX 描述 / Vorbis 注释名 / 格式专有键），不区分大小写
+fn find_raw<'a>(tag: &'a Tag, key: &str) -> Option<&'a str> {
+    // 允许以 "TXXX:描述" 的形式指定 ID3v2 自定义帧
+    let key = key.strip_prefix("TXXX:").unwrap_or(key);
+
+    tag.items().find_map(|item| {
+        let matches = match item.key() {
+            ItemKey::Unknown(k) => k.eq_ignore_ascii_case(key),
+            other => other
+                .map_key(tag.tag_type(), true)
+                .is_some_and(|k| k.eq_ignore_ascii_case(key)),
+        };
+        if matches {
+            item.value().text()
+        } else {
+            None
+        }
+    })
+}
+
+/// 读取指定键的第一个标签值（键名不区分大小写）
+/// 常用键映射到 lofty 的 ItemKey，未知键按原始键名（TXXX / Vorbis 注释）查找
+pub fn read_tag_field(path: &Path, key: &str) -> Result<Option<String>, String> {
+    let tagged_file = Probe::open(path)
+        .map_err(|e| format!("无法打开文件: {}", e))?
+        .read()
+        .map_err(|e| format!("无法读取音频文件: {}", e))?;
+
+    let upper = key.trim().to_uppercase();
+    let item_key = well_known_key(&upper);
+
+    // 主标签优先，其次文件中的其他标签
+    let primary_type = tagged_file.primary_tag().map(|t| t.tag_type());
+    let mut tags: Vec<&Tag> = tagged_file.tags().iter().collect();
+    tags.sort_by_key(|t| Some(t.tag_type()) != primary_type);
+
+    for tag in tags {
+        let value = item_key
+            .as_ref()
+            .and_then(|k| tag.get_string(k))
+            .or_else(|| find_raw(tag, &upper));
+        if let Some(value) = value {
+            return Ok(Some(value.to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// 以可写方式打开音频文件：只读文件直接报错，而不是保存时静默失败
+fn open_for_write(path: &Path) -> Result<TaggedFile, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("无法获取文件信息: {}", e))?;
+    if metadata.permissions().readonly() {
+        return Err(format!("文件为只读，无法写入标签: {}", path.display()));
+    }
+
+    Probe::open(path)
+        .map_err(|e| format!("无法打开文件: {}", e))?
+        .read()
+        .map_err(|e| format!("无法读取音频文件: {}", e))
+}
+
+/// 获取主标签，文件没有标签时创建该格式的默认标签
+fn primary_tag_or_insert(tagged_file: &mut TaggedFile) -> &mut Tag {
+    if tagged_file.primary_tag().is_none() {
+        let tag_type = tagged_file.primary_tag_type();
+        tagged_file.insert_tag(Tag::new(tag_type));
+    }
+    tagged_file.primary_tag_mut().expect("primary tag was just inserted")
+}
+
+/// 保存标签到文件
+fn save(tagged_file: &TaggedFile, path: &Path) -> Result<(), String> {
+    tagged_file
+        .save_to_path(path, WriteOptions::default())
+        .map_err(|e| format!("保存标签失败: {}", e))
+}
+
+/// 写回编辑后的标签：只修改提供的字段，保留其他标签和主标签类型
+pub fn write_metadata(path: &Path, changes: &TagChanges) -> Result<(), String> {
+    let mut tagged_file = open_for_write(path)?;
+    let tag = primary_tag_or_insert(&mut tagged_file);
+
+    if let Some(title) = &changes.title {
+        tag.set_title(title.clone());
+    }
+    if let Some(artist) = &changes.artist {
+        tag.set_artist(artist.clone());
+    }
+    if let Some(album) = &changes.album {
+        tag.set_album(album.clone());
+    }
+    if let Some(track_no) = changes.track_no {
+        tag.set_track(track_no);
+    }
+    if let Some(track_total) = changes.track_total {
+        tag.set_track_total(track_total);
+    }
+
+    save(&tagged_file, path)
+}
+
+/// 解析前端传入的图片类型名（如 "front" / "back" / "artist"），默认为封面正面
+pub fn parse_picture_type(name: Option<&str>) -> Result<PictureType, String> {
+    let pic_type = match name.map(|n| n.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("front") | Some("cover") | Some("coverfront") => {
+            PictureType::CoverFront
+        }
+        Some("back") | Some("coverback") => PictureType::CoverBack,
+        Some("media") | Some("disc") => PictureType::Media,
+        Some("leaflet") | Some("booklet") => PictureType::Leaflet,
+        Some("artist") => PictureType::Artist,
+        Some("leadartist") => PictureType::LeadArtist,
+        Some("band") => PictureType::Band,
+        Some("icon") => PictureType::Icon,
+        Some("illustration") => PictureType::Illustration,
+        Some("other") => PictureType::Other,
+        Some(other) => return Err(format!("未知的图片类型: {}", other)),
+    };
+    Ok(pic_type)
+}
+
+/// 将图片写入主标签；`replace` 为 true 时先移除同类型的已有图片
+pub fn embed_picture(
+    path: &Path,
+    data: Vec<u8>,
+    mime_type: &str,
+    pic_type: PictureType,
+    replace: bool,
+) -> Result<(), String> {
+    let mut tagged_file = open_for_write(path)?;
+    let tag = primary_tag_or_insert(&mut tagged_file);
+
+    if replace {
+        tag.remove_picture_type(pic_type);
+    }
+    tag.push_picture(Picture::new_unchecked(
+        pic_type,
+        Some(MimeType::from_str(mime_type)),
+        None,
+        data,
+    ));
+
+    save(&tagged_file, path)
+}
@@ -0,0 +1,110 @@
+//! 进度条波形概览：流式解码音频，按时间均分为若干段并取每段峰值
+//!
+//! 解码时不保留整段 PCM：先以单帧为一块记录峰值，块数超过目标段数的两倍时
+//! 两两合并、块长加倍，因此内存占用只与段数有关，且不依赖容器给出的时长。
+//! 结果以小端 f32 写入缓存目录下的 `{歌曲 ID}-{段数}.bin`，文件修改后自动重新生成。
+
+use std::fs;
+use std::path::Path;
+
+use crate::audio_engine::decoder::AudioDecoder;
+use crate::utils::audio::song_id;
+
+/// 段数上限，超出时按上限生成（进度条用不到更细的分辨率）
+pub const MAX_BUCKETS: usize = 10_000;
+
+/// 生成波形概览（带缓存），返回 `buckets` 个归一化到 0 ~ 1 的峰值，
+/// `buckets` 超过 `MAX_BUCKETS` 时按上限生成
+pub fn load_or_generate(
+    path: &Path,
+    buckets: usize,
+    cache_dir: &Path,
+) -> Result<Vec<f32>, String> {
+    if buckets == 0 {
+        return Err("段数必须大于 0".to_string());
+    }
+    let buckets = buckets.min(MAX_BUCKETS);
+
+    let cache_path = cache_dir.join(format!("{}-{}.bin", song_id(path), buckets));
+    if let Some(peaks) = read_cache(&cache_path, path, buckets) {
+        return Ok(peaks);
+    }
+
+    let peaks = generate(path, buckets)?;
+    let bytes: Vec<u8> = peaks.iter().flat_map(|p| p.to_le_bytes()).collect();
+    // 缓存写入失败不影响本次结果，下次请求时重新生成
+    let _ = fs::create_dir_all(cache_dir).and_then(|_| fs::write(&cache_path, bytes));
+    Ok(peaks)
+}
+
+/// 读取缓存：缓存早于音频文件或长度不符时视为失效
+fn read_cache(cache_path: &Path, source: &Path, buckets: usize) -> Option<Vec<f32>> {
+    let cached_at = fs::metadata(cache_path).and_then(|m| m.modified()).ok()?;
+    let modified_at = fs::metadata(source).and_then(|m| m.modified()).ok()?;
+    if cached_at < modified_at {
+        return None;
+    }
+
+    let bytes = fs::read(cache_path).ok()?;
+    if bytes.len() != buckets * 4 {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    )
+}
+
+/// 流式解码并计算波形概览
+pub fn generate(path: &Path, buckets: usize) -> Result<Vec<f32>, String> {
+    let source = path.to_str().ok_or("路径不是有效的 UTF-8")?;
+    let mut decoder = AudioDecoder::open(source)?;
+    let channels = decoder.info.channels.max(1);
+
+    // 第 k 块覆盖 [k * block_frames, (k + 1) * block_frames) 帧
+    let mut blocks: Vec<f32> = Vec::with_capacity(buckets * 2);
+    let mut block_frames = 1u64;
+    let mut frames = 0u64;
+
+    while let Some(samples) = decoder.decode_next()? {
+        for frame in samples.chunks(channels) {
+            let level = frame.iter().fold(0.0f32, |acc, s| acc.max(s.abs()));
+            let mut index = (frames / block_frames) as usize;
+            if index == buckets * 2 {
+                blocks = blocks.chunks(2).map(|pair| pair[0].max(pair[1])).collect();
+                block_frames *= 2;
+                index = (frames / block_frames) as usize;
+            }
+            if index == blocks.len() {
+                blocks.push(0.0);
+            }
+            blocks[index] = blocks[index].max(level);
+            frames += 1;
+        }
+    }
+
+    if frames == 0 {
+        return Err("未解码到音频数据".to_string());
+    }
+
+    // 按总帧数把块映射到目标段，每段取所覆盖块的最大值
+    let mut peaks: Vec<f32> = (0..buckets as u64)
+        .map(|b| {
+            let start = (b * frames / buckets as u64 / block_frames) as usize;
+            let end = ((b + 1) * frames).div_ceil(buckets as u64 * block_frames) as usize;
+            let end = end.clamp(start + 1, blocks.len());
+            blocks[start..end]
+                .iter()
+                .fold(0.0f32, |acc, &p| acc.max(p))
+        })
+        .collect();
+
+    // 归一化：最高的一段为 1.0，静音文件保持全 0
+    let max = peaks.iter().fold(0.0f32, |acc, &p| acc.max(p));
+    if max > 0.0 {
+        peaks.iter_mut().for_each(|p| *p /= max);
+    }
+    Ok(peaks)
+}
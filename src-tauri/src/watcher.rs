@@ -5,10 +5,12 @@
 pub mod desktop {
     use std::collections::HashSet;
     use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::{Arc, Mutex};
     use std::time::{Duration, Instant};
 
     use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+    use serde::Serialize;
     use tauri::{AppHandle, Emitter, Manager};
 
     use crate::commands::CoverCacheState;
@@ -16,10 +18,22 @@ pub mod desktop {
     use crate::utils::audio;
     use crate::utils::cover::extract_and_cache_cover;
 
+    /// Payload of the "library-files-changed" event: the paths that changed
+    #[derive(Debug, Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LibraryChange {
+        /// Added or modified audio files whose metadata was re-read
+        pub updated: Vec<String>,
+        /// Files removed from the library
+        pub removed: Vec<String>,
+    }
+
     /// Shared state for the file watcher
     pub struct WatcherState {
         watcher: Option<RecommendedWatcher>,
         watched_dirs: Vec<String>,
+        /// Tells the debounce thread of the current watcher to exit
+        stop_flag: Arc<AtomicBool>,
     }
 
     impl WatcherState {
@@ -27,8 +41,16 @@ pub mod desktop {
             Self {
                 watcher: None,
                 watched_dirs: Vec::new(),
+                stop_flag: Arc::new(AtomicBool::new(false)),
             }
         }
+
+        /// Drop the watcher and end its debounce thread
+        fn stop(&mut self) {
+            self.watcher = None;
+            self.watched_dirs.clear();
+            self.stop_flag.store(true, Ordering::Relaxed);
+        }
     }
 
     /// Managed Tauri state wrapper
@@ -47,13 +69,15 @@ pub mod desktop {
             .map_err(|e| format!("Failed to lock watcher state: {}", e))?;
 
         // Stop existing watcher if any
-        state.watcher = None;
-        state.watched_dirs.clear();
+        state.stop();
 
         if directories.is_empty() {
             return Ok(());
         }
 
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        state.stop_flag = stop_flag.clone();
+
         // Debounce state: collect changed paths, process after 500ms of quiet
         let pending_paths: Arc<Mutex<HashSet<PathBuf>>> = Arc::new(Mutex::new(HashSet::new()));
         let last_event_time: Arc<Mutex<Instant>> = Arc::new(Mutex::new(Instant::now()));
@@ -66,6 +90,10 @@ pub mod desktop {
             loop {
                 std::thread::sleep(Duration::from_millis(500));
 
+                if stop_flag.load(Ordering::Relaxed) {
+                    break;
+                }
+
                 let should_process = {
                     let last = last_time_for_debounce.lock().unwrap();
                     let pending = pending_for_debounce.lock().unwrap();
@@ -143,8 +171,7 @@ pub mod desktop {
             .lock()
             .map_err(|e| format!("Failed to lock watcher state: {}", e))?;
 
-        state.watcher = None;
-        state.watched_dirs.clear();
+        state.stop();
         Ok(())
     }
 
@@ -172,7 +199,8 @@ pub mod desktop {
             }
         }
 
-        let mut changed = false;
+        let mut updated: Vec<String> = Vec::new();
+        let mut removed: Vec<String> = Vec::new();
 
         // Scan new/modified files
         if !to_scan.is_empty() {
@@ -196,6 +224,9 @@ pub mod desktop {
                             server_song_id: None,
                             stream_info: None,
                             file_modified: Some(song.file_modified),
+                            album_artist: song.album_artist,
+                            musicbrainz_album_id: song.musicbrainz_album_id,
+                            compilation: song.compilation,
                         }
                     })
                 })
@@ -204,7 +235,7 @@ pub mod desktop {
             if !song_inputs.is_empty() {
                 if let Ok(mut conn) = db_state.0.lock() {
                     let _ = db::songs::save_songs(&mut conn, &song_inputs, "local", None);
-                    changed = true;
+                    updated = song_inputs.into_iter().map(|song| song.file_path).collect();
                 }
             }
         }
@@ -218,12 +249,13 @@ pub mod desktop {
                         [path_str],
                     );
                 }
-                changed = true;
+                removed = to_delete;
             }
         }
 
-        // Notify frontend
-        if changed {
+        // Notify frontend, with the changed paths for listeners that update incrementally
+        if !updated.is_empty() || !removed.is_empty() {
+            let _ = app_handle.emit("library-files-changed", LibraryChange { updated, removed });
             let _ = app_handle.emit("library-updated", ());
         }
     }